@@ -7,7 +7,7 @@ pub enum AmmError {
     DefaultError,
     #[msg("Offer Expired")]
     OfferExpired,
-    #[msg("This pool is locked")]
+    #[msg("This pool operation is paused")]
     PoolLocked,
     #[msg("Slippage exceeded")]
     SlippageExceded,
@@ -17,6 +17,22 @@ pub enum AmmError {
     Underflow,
     #[msg("Invalid Token")]
     InvalidToken,
+    #[msg("Wrapped SOL account must be empty before the call")]
+    NativeAccountNotEmpty,
+    #[msg("Pool mints must differ")]
+    IdenticalMints,
+    #[msg("Non-transferable mints cannot be pooled")]
+    NonTransferableMint,
+    #[msg("Mint carries an extension the pool creator did not opt in to")]
+    MintExtensionNotAllowed,
+    #[msg("Mint's transfer hook program is not in the hook registry")]
+    HookProgramNotApproved,
+    #[msg("Hook program data does not match the approved build")]
+    HookProgramDataMismatch,
+    #[msg("Hook registry is full")]
+    HookRegistryFull,
+    #[msg("Hook program is already approved")]
+    HookAlreadyApproved,
     #[msg("Actual Liquidity is Less than minimum")]
     LiquidityLessThanMinium,
     #[msg("No Liquidity in Pool")]
@@ -31,6 +47,12 @@ pub enum AmmError {
     InvalidAuthority,
     #[msg("No update authority set")]
     NoAuthoritySet,
+    #[msg("No authority transfer pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending authority")]
+    InvalidPendingAuthority,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
     #[msg("Invalid Amount")]
     InvalidAmount,
     #[msg("Invalid precision")]
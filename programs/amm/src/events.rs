@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub config: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub config: Pubkey,
+    pub signer: Pubkey,
+    pub paused: u8,
+}
+
+#[event]
+pub struct ProtocolFeesCollected {
+    pub config: Pubkey,
+    pub treasury: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+#[event]
+pub struct ReservesSynced {
+    pub config: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+#[event]
+pub struct ReservesSkimmed {
+    pub config: Pubkey,
+    pub recipient: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+#[event]
+pub struct HookApproved {
+    pub program_id: Pubkey,
+    pub program_data_hash: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct HookRevoked {
+    pub program_id: Pubkey,
+}
+
+#[event]
+pub struct HookRegistryAdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct HookRegistryAdminAccepted {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
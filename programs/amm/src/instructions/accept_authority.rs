@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{config, error::AmmError, events::AuthorityTransferAccepted};
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, config>,
+}
+
+impl<'info> AcceptAuthority<'info> {
+    pub fn accept_authority(&mut self) -> Result<()> {
+        let pending = self.config.pending_authority.ok_or(AmmError::NoPendingAuthority)?;
+        require_keys_eq!(pending, self.pending_authority.key(), AmmError::InvalidPendingAuthority);
+        // A pending transfer can only exist while an authority is set
+        let previous_authority = self.config.authority.ok_or(AmmError::NoAuthoritySet)?;
+
+        self.config.authority = Some(pending);
+        self.config.pending_authority = None;
+
+        emit!(AuthorityTransferAccepted {
+            config: self.config.key(),
+            previous_authority,
+            new_authority: pending,
+        });
+        Ok(())
+    }
+}
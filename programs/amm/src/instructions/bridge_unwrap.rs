@@ -3,9 +3,10 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
         Mint, TokenAccount, TokenInterface, 
-        transfer_checked, burn, TransferChecked, Burn
+        burn, Burn
     },
 };
+use crate::token_transfer::transfer_checked_with_hook;
 use crate::state::*;
 use crate::error::*;
 
@@ -19,7 +20,8 @@ pub struct UnwrapFromPool<'info> {
     #[account(
         seeds = [b"config", amm_config.seed.to_le_bytes().as_ref()],
         bump = amm_config.config_bump,
-        constraint = amm_config.is_bridge_pool @ AmmError::NotBridgePool
+        constraint = amm_config.is_bridge_pool @ AmmError::NotBridgePool,
+        constraint = !amm_config.is_paused(PAUSE_BRIDGE_UNWRAP) @ AmmError::PoolLocked
     )]
     pub amm_config: Account<'info, config>,
     
@@ -73,7 +75,10 @@ pub struct UnwrapFromPool<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn unwrap_from_pool(ctx: Context<UnwrapFromPool>, amount: u64) -> Result<()> {
+pub fn unwrap_from_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnwrapFromPool<'info>>,
+    amount: u64,
+) -> Result<()> {
     require!(amount > 0, AmmError::InvalidAmount);
     
     let bridge_pool_config = &ctx.accounts.bridge_pool_config;
@@ -103,19 +108,16 @@ pub fn unwrap_from_pool(ctx: Context<UnwrapFromPool>, amount: u64) -> Result<()>
         &[bridge_pool_config.bump],
     ];
     
-    transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_2022_program.to_account_info(),
-            TransferChecked {
-                from: ctx.accounts.pool_vault_token_account.to_account_info(),
-                mint: ctx.accounts.restricted_token_mint.to_account_info(),
-                to: ctx.accounts.user_restricted_token_account.to_account_info(),
-                authority: ctx.accounts.bridge_pool_config.to_account_info(),
-            },
-            &[bridge_pool_signer_seeds],
-        ),
+    transfer_checked_with_hook(
+        &ctx.accounts.token_2022_program.to_account_info(),
+        &ctx.accounts.pool_vault_token_account.to_account_info(),
+        &ctx.accounts.restricted_token_mint.to_account_info(),
+        &ctx.accounts.user_restricted_token_account.to_account_info(),
+        &ctx.accounts.bridge_pool_config.to_account_info(),
+        ctx.remaining_accounts,
         amount,
         mint_decimals,
+        &[bridge_pool_signer_seeds],
     ).map_err(|_| AmmError::HookValidationFailed)?;
     
     msg!("Unlocked {} Token-2022 tokens to user", amount);
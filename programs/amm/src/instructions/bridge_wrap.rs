@@ -21,7 +21,8 @@ pub struct WrapForPool<'info> {
         seeds = [b"config", amm_config.seed.to_le_bytes().as_ref()],
         bump = amm_config.config_bump,
         constraint = amm_config.is_bridge_pool @ AmmError::NotBridgePool,
-        constraint = amm_config.bridge_config.is_some() @ AmmError::BridgeConfigNotSet
+        constraint = amm_config.bridge_config.is_some() @ AmmError::BridgeConfigNotSet,
+        constraint = !amm_config.is_paused(PAUSE_BRIDGE_WRAP) @ AmmError::PoolLocked
     )]
     pub amm_config: Account<'info, config>,
     
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{config, error::AmmError, events::ProtocolFeesCollected, token_transfer::transfer_checked_with_hook};
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, config>,
+    #[account(address = config.mint_a @ AmmError::InvalidToken, mint::token_program = token_program_a)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(address = config.mint_b @ AmmError::InvalidToken, mint::token_program = token_program_b)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Owner of the treasury token accounts; only recorded in the event
+    pub treasury: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint_a, token::authority = treasury, token::token_program = token_program_a)]
+    pub treasury_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint_b, token::authority = treasury, token::token_program = token_program_b)]
+    pub treasury_b: InterfaceAccount<'info, TokenAccount>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CollectProtocolFees<'info> {
+    pub fn collect_protocol_fees(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+
+        let amount_a = self.config.protocol_fees_a;
+        let amount_b = self.config.protocol_fees_b;
+        let seeds = &[
+            b"config".as_ref(),
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        if amount_a != 0 {
+            transfer_checked_with_hook(
+                &self.token_program_a.to_account_info(),
+                &self.vault_a.to_account_info(),
+                &self.mint_a.to_account_info(),
+                &self.treasury_a.to_account_info(),
+                &self.config.to_account_info(),
+                remaining_accounts,
+                amount_a,
+                self.mint_a.decimals,
+                signer_seeds,
+            )?;
+        }
+        if amount_b != 0 {
+            transfer_checked_with_hook(
+                &self.token_program_b.to_account_info(),
+                &self.vault_b.to_account_info(),
+                &self.mint_b.to_account_info(),
+                &self.treasury_b.to_account_info(),
+                &self.config.to_account_info(),
+                remaining_accounts,
+                amount_b,
+                self.mint_b.decimals,
+                signer_seeds,
+            )?;
+        }
+
+        self.config.protocol_fees_a = 0;
+        self.config.protocol_fees_b = 0;
+
+        emit!(ProtocolFeesCollected {
+            config: self.config.key(),
+            treasury: self.treasury.key(),
+            amount_a,
+            amount_b,
+        });
+        Ok(())
+    }
+}
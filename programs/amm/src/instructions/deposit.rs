@@ -1,8 +1,8 @@
-use anchor_lang::{prelude::*};
-use anchor_spl::{associated_token::AssociatedToken, token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked}};
-use constant_product_curve::ConstantProduct;
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface}};
+use constant_product_curve::{ConstantProduct, LiquidityPair};
 
-use crate::{config, error::AmmError};
+use crate::{config, error::AmmError, math::{balanced_deposit, first_deposit_lp, zap_swap_amount}, token_transfer::{amount_after_transfer_fee, amount_before_transfer_fee, is_native_mint, transfer_checked_with_hook, unwrap_if_native, wrap_if_native}, MINIMUM_LIQUIDITY, PAUSE_DEPOSIT, PAUSE_SWAP};
 
 
 #[derive(Accounts)]
@@ -10,72 +10,193 @@ use crate::{config, error::AmmError};
 pub struct Deposit<'info>{
 #[account(mut)]
     pub signer:Signer<'info>,
-    
- pub mintx:InterfaceAccount<'info,Mint>,
-/// CHECK: NATIVE_MINT_2022 - special system-owned mint
-pub minty: AccountInfo<'info>,
-#[account(mut)]
-pub user_x:InterfaceAccount<'info,TokenAccount>,
-#[account(mut)]
-pub user_y:InterfaceAccount<'info,TokenAccount>,
-#[account(init_if_needed,associated_token::mint=lp_token,associated_token::authority=signer,payer=signer)]
+
+#[account(address=config.mint_a @ AmmError::InvalidToken, mint::token_program=token_program_a)]
+ pub mint_a:InterfaceAccount<'info,Mint>,
+#[account(address=config.mint_b @ AmmError::InvalidToken, mint::token_program=token_program_b)]
+ pub mint_b:InterfaceAccount<'info,Mint>,
+#[account(mut, token::mint=mint_a, token::authority=signer, token::token_program=token_program_a, constraint=!is_native_mint(&mint_a.key()) || user_a.amount==0 @ AmmError::NativeAccountNotEmpty)]
+pub user_a:InterfaceAccount<'info,TokenAccount>,
+#[account(mut, token::mint=mint_b, token::authority=signer, token::token_program=token_program_b, constraint=!is_native_mint(&mint_b.key()) || user_b.amount==0 @ AmmError::NativeAccountNotEmpty)]
+pub user_b:InterfaceAccount<'info,TokenAccount>,
+#[account(init_if_needed,associated_token::mint=lp_token,associated_token::authority=signer,associated_token::token_program=token_program,payer=signer)]
 pub user_lp:InterfaceAccount<'info,TokenAccount>,
 #[account(mut,seeds=[b"lp",config.key().as_ref()],bump=config.lp_bump)]
 pub lp_token:InterfaceAccount<'info,Mint>,
-#[account(init_if_needed,payer=signer,associated_token::mint=mintx,associated_token::authority=config)]
-pub vault_x:InterfaceAccount<'info,TokenAccount>,
-#[account(init_if_needed,payer=signer,associated_token::mint=minty,associated_token::authority=config)]
-pub vault_y:InterfaceAccount<'info,TokenAccount>,
+/// CHECK: Dead PDA that owns the locked minimum liquidity; nothing ever signs for it
+#[account(seeds=[b"locked_liquidity",config.key().as_ref()],bump)]
+pub locked_liquidity:UncheckedAccount<'info>,
+#[account(init_if_needed,associated_token::mint=lp_token,associated_token::authority=locked_liquidity,associated_token::token_program=token_program,payer=signer)]
+pub locked_lp:InterfaceAccount<'info,TokenAccount>,
+#[account(mut,associated_token::mint=mint_a,associated_token::authority=config,associated_token::token_program=token_program_a)]
+pub vault_a:InterfaceAccount<'info,TokenAccount>,
+#[account(mut,associated_token::mint=mint_b,associated_token::authority=config,associated_token::token_program=token_program_b)]
+pub vault_b:InterfaceAccount<'info,TokenAccount>,
 #[account(mut,seeds=[b"config",config.seed.to_le_bytes().as_ref()],bump=config.config_bump)]
 pub config:Account<'info,config>,
 pub system_program:Program<'info,System>,
+// Program of the LP mint
 pub token_program:Interface<'info,TokenInterface>,
+// Owning programs of each side; hook accounts for both sides come in via remaining_accounts
+pub token_program_a:Interface<'info,TokenInterface>,
+pub token_program_b:Interface<'info,TokenInterface>,
 pub associated_token_program:Program<'info,AssociatedToken>
 }
 impl<'info>  Deposit <'info>{
-    pub fn deposit(&mut self,amount:u64,max_x:u64,max_y:u64)->Result<()>{
-        require!(self.config.locked == false, AmmError::PoolLocked);
+    pub fn deposit(&mut self,amount:u64,max_a:u64,max_b:u64,expiration:i64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+        require!(!self.config.is_paused(PAUSE_DEPOSIT), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
        require!(amount!=0,AmmError::InvalidAmount);
 
-    println!("Vault X amount: {}", self.vault_x.amount);
-println!("Vault Y amount: {}", self.vault_y.amount);
-    println!("LP supply: {}", self.lp_token.supply);
-       let (x,y)=match self.lp_token.supply==0 && self.vault_x.amount==0 && self.vault_y.amount==0  {
-            true =>(max_x,max_y),
-            false=>{let amount=ConstantProduct::xy_deposit_amounts_from_l(self.vault_x.amount, self.vault_y.amount, self.lp_token.supply, amount, 6).unwrap();
-                (amount.x,amount.y)}
+       let (reserve_a,reserve_b)=(self.config.reserve_a,self.config.reserve_b);
+       let is_first_deposit=self.is_first_deposit();
+       let (a,b,lp_out)=match is_first_deposit  {
+            // LP supply comes from the deposited amounts, not the caller; `amount` is the minimum accepted
+            true =>{let (a,b)=(self.net_in(true,max_a)?,self.net_in(false,max_b)?);
+                let lp_out=first_deposit_lp(a,b)?;
+                require!(lp_out>=amount,AmmError::SlippageExceded);
+                (a,b,lp_out)},
+            false=>{let amounts=ConstantProduct::xy_deposit_amounts_from_l(reserve_a, reserve_b, self.lp_token.supply, amount, 6).map_err(|_|AmmError::CurveError)?;
+                (amounts.x,amounts.y,amount)}
        };
-       require!(x<=max_x||y<=max_y,AmmError::SlippageExceded);
-       self.deposittoken(true, x)?;
-       self.deposittoken(false, y)?;
-       self.mint(amount)
-    
+       require!(self.gross_in(true,a)?<=max_a&&self.gross_in(false,b)?<=max_b,AmmError::SlippageExceded);
+       self.add_liquidity(a, b, lp_out, is_first_deposit, remaining_accounts)
+    }
+    // Pulls the largest balanced deposit that fits in the desired amounts; nothing above it leaves the wallet
+    pub fn deposit_by_amounts(&mut self,desired_a:u64,desired_b:u64,min_lp_out:u64,expiration:i64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+        require!(!self.config.is_paused(PAUSE_DEPOSIT), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
+        require!(desired_a!=0 && desired_b!=0,AmmError::InvalidAmount);
+
+        let is_first_deposit=self.is_first_deposit();
+        let (net_a,net_b)=(self.net_in(true,desired_a)?,self.net_in(false,desired_b)?);
+        let (a,b,lp_out)=match is_first_deposit {
+            true=>(net_a,net_b,first_deposit_lp(net_a,net_b)?),
+            false=>balanced_deposit(self.config.reserve_a,self.config.reserve_b,self.lp_token.supply,net_a,net_b)?
+        };
+        require!(lp_out!=0,AmmError::InvalidAmount);
+        require!(lp_out>=min_lp_out,AmmError::SlippageExceded);
+        require!(self.gross_in(true,a)?<=desired_a&&self.gross_in(false,b)?<=desired_b,AmmError::SlippageExceded);
+        self.add_liquidity(a, b, lp_out, is_first_deposit, remaining_accounts)
     }
-    pub fn deposittoken(&self,is_x:bool,amount:u64)->Result<()>{
-      if is_x {
-          // Transfer Token-2022 with transfer hook
-          let account=TransferChecked{
-            from:self.user_x.to_account_info(),
-            mint: self.mintx.to_account_info(),
-            to:self.vault_x.to_account_info(),
-            authority:self.signer.to_account_info()
-          };
-         let cpi_context=CpiContext::new(self.token_program.to_account_info(), account);
-          transfer_checked(cpi_context, amount, self.mintx.decimals)
+    // Zap: swaps the optimal part of `amount_in` through the curve, then deposits both legs.
+    // The swapped-out leg never leaves the vault, so only the input side is transferred in.
+    pub fn deposit_single_sided(&mut self,is_a:bool,amount_in:u64,min_lp_out:u64,expiration:i64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+        require!(!self.config.is_paused(PAUSE_DEPOSIT), AmmError::PoolLocked);
+        require!(!self.config.is_paused(PAUSE_SWAP), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
+        require!(amount_in!=0,AmmError::InvalidAmount);
+        require!(!self.is_first_deposit(),AmmError::NoLiquidityInPool);
+
+        let (reserve_a,reserve_b)=(self.config.reserve_a,self.config.reserve_b);
+        let reserve_in=if is_a {reserve_a} else {reserve_b};
+        // Split what reaches the vault; the transfer fee on the input never enters the curve
+        let net_amount_in=self.net_in(is_a,amount_in)?;
+        let swap_in=zap_swap_amount(reserve_in,net_amount_in,self.config.fee)?;
+        require!(swap_in!=0,AmmError::InvalidAmount);
+
+        let mut curve=ConstantProduct::init(reserve_a,reserve_b,self.lp_token.supply,self.config.fee,None)
+            .map_err(|_| AmmError::CurveError)?;
+        let pair=if is_a {LiquidityPair::X} else {LiquidityPair::Y};
+        let swap_result=curve.swap(pair,swap_in,0).map_err(|_| AmmError::CurveError)?;
+        let protocol_cut=self.config.accrue_protocol_fee(is_a,swap_result.fee)?;
+        let swap_in_to_reserve=swap_in-protocol_cut;
+
+        // Deposit the rest of the input plus the swap output against post-swap reserves
+        let remaining=net_amount_in-swap_in;
+        let (a,b,lp_out)=if is_a {
+            balanced_deposit(
+                reserve_a.checked_add(swap_in_to_reserve).ok_or(AmmError::Overflow)?,
+                reserve_b.checked_sub(swap_result.withdraw).ok_or(AmmError::Underflow)?,
+                self.lp_token.supply,remaining,swap_result.withdraw)?
+        } else {
+            balanced_deposit(
+                reserve_a.checked_sub(swap_result.withdraw).ok_or(AmmError::Underflow)?,
+                reserve_b.checked_add(swap_in_to_reserve).ok_or(AmmError::Overflow)?,
+                self.lp_token.supply,swap_result.withdraw,remaining)?
+        };
+        require!(lp_out!=0,AmmError::InvalidAmount);
+        require!(lp_out>=min_lp_out,AmmError::SlippageExceded);
+
+        // Swap output the deposit could not use stays in the vault, so the output reserve is unchanged
+        let deposit_in=if is_a {a} else {b};
+        let amount_pulled=self.gross_in(is_a,swap_in.checked_add(deposit_in).ok_or(AmmError::Overflow)?)?;
+        require!(amount_pulled<=amount_in,AmmError::SlippageExceded);
+        self.deposit_token(is_a, amount_pulled, remaining_accounts)?;
+        let reserve_in_after=reserve_in
+            .checked_add(swap_in_to_reserve).and_then(|r| r.checked_add(deposit_in))
+            .ok_or(AmmError::Overflow)?;
+        if is_a {
+            self.config.reserve_a=reserve_in_after;
+        } else {
+            self.config.reserve_b=reserve_in_after;
+        }
+        self.unwrap_user_token(is_a)?;
+        self.mint(self.user_lp.to_account_info(),lp_out)
+    }
+    pub fn is_first_deposit(&self)->bool{
+        self.lp_token.supply==0 && self.config.reserve_a==0 && self.config.reserve_b==0
+    }
+    // `a` and `b` are what must land in the vaults; the senders cover any transfer fee on top
+    pub fn add_liquidity(&mut self,a:u64,b:u64,lp_out:u64,is_first_deposit:bool,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+       self.deposit_token(true, self.gross_in(true,a)?, remaining_accounts)?;
+       self.deposit_token(false, self.gross_in(false,b)?, remaining_accounts)?;
+       self.config.reserve_a=self.config.reserve_a.checked_add(a).ok_or(AmmError::Overflow)?;
+       self.config.reserve_b=self.config.reserve_b.checked_add(b).ok_or(AmmError::Overflow)?;
+       self.unwrap_user_token(true)?;
+       self.unwrap_user_token(false)?;
+       if is_first_deposit {
+           self.mint(self.locked_lp.to_account_info(),MINIMUM_LIQUIDITY)?;
+       }
+       self.mint(self.user_lp.to_account_info(),lp_out)
+    }
+    // Amount reaching the vault when the user sends `amount`, after any Token-2022 transfer fee
+    pub fn net_in(&self,is_a:bool,amount:u64)->Result<u64>{
+      let mint=if is_a {self.mint_a.to_account_info()} else {self.mint_b.to_account_info()};
+      amount_after_transfer_fee(&mint,amount)
+    }
+    // Amount the user has to send for `amount` to reach the vault
+    pub fn gross_in(&self,is_a:bool,amount:u64)->Result<u64>{
+      let mint=if is_a {self.mint_a.to_account_info()} else {self.mint_b.to_account_info()};
+      amount_before_transfer_fee(&mint,amount)
+    }
+    // Each side goes through its own program; a hooked mint finds its extra accounts by key
+    pub fn deposit_token(&self,is_a:bool,amount:u64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+      let (token_program,from,mint,to)=if is_a {
+          (&self.token_program_a,&self.user_a,&self.mint_a,&self.vault_a)
+      } else {
+          (&self.token_program_b,&self.user_b,&self.mint_b,&self.vault_b)
+      };
+      wrap_if_native(
+        &mint.key(),
+        &self.system_program.to_account_info(),
+        &token_program.to_account_info(),
+        &self.signer.to_account_info(),
+        &from.to_account_info(),
+        amount,
+      )?;
+      transfer_checked_with_hook(
+        &token_program.to_account_info(),
+        &from.to_account_info(),
+        &mint.to_account_info(),
+        &to.to_account_info(),
+        &self.signer.to_account_info(),
+        remaining_accounts,
+        amount,
+        mint.decimals,
+        &[],
+      )
+    }
+    pub fn unwrap_user_token(&self,is_a:bool)->Result<()>{
+      let (token_program,account,mint)=if is_a {
+          (&self.token_program_a,&self.user_a,&self.mint_a)
       } else {
-          // Transfer WSOL (NATIVE_MINT_2022)
-          let account=TransferChecked{
-            from:self.user_y.to_account_info(),
-            mint: self.minty.to_account_info(),
-            to:self.vault_y.to_account_info(),
-            authority:self.signer.to_account_info()
-          };
-         let cpi_context=CpiContext::new(self.token_program.to_account_info(), account);
-          transfer_checked(cpi_context, amount, 9) // WSOL has 9 decimals
-      }
+          (&self.token_program_b,&self.user_b,&self.mint_b)
+      };
+      unwrap_if_native(&mint.key(),&token_program.to_account_info(),&self.signer.to_account_info(),&account.to_account_info())
     }
-    pub fn mint(&self,amount:u64)->Result<()>{
-        let mint=MintTo{mint:self.lp_token.to_account_info(),to:self.user_lp.to_account_info(),authority:self.config.to_account_info()};
+    pub fn mint(&self,to:AccountInfo<'info>,amount:u64)->Result<()>{
+        let mint=MintTo{mint:self.lp_token.to_account_info(),to,authority:self.config.to_account_info()};
         let seeds = &[
             b"config".as_ref(),
             &self.config.seed.to_le_bytes(),
@@ -85,4 +206,4 @@ println!("Vault Y amount: {}", self.vault_y.amount);
         let cpi_context=CpiContext::new_with_signer(self.token_program.to_account_info(),mint, signer_seeds);
         mint_to(cpi_context, amount)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    events::{HookApproved, HookRegistryAdminAccepted, HookRegistryAdminProposed, HookRevoked},
+    program::Amm,
+    ApprovedHook, HookRegistry, MAX_APPROVED_HOOKS,
+};
+
+#[derive(Accounts)]
+pub struct InitializeHookRegistry<'info> {
+    // Only the AMM's upgrade authority can create the registry
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ AmmError::Unauthorized)]
+    pub program: Program<'info, Amm>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(signer.key()) @ AmmError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + HookRegistry::INIT_SPACE,
+        seeds = [b"hook-registry"],
+        bump
+    )]
+    pub hook_registry: Account<'info, HookRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeHookRegistry<'info> {
+    pub fn initialize_hook_registry(&mut self, admin: Pubkey, bump: u8) -> Result<()> {
+        self.hook_registry.set_inner(HookRegistry {
+            admin,
+            pending_admin: None,
+            hooks: Vec::new(),
+            bump,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateHookRegistry<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"hook-registry"],
+        bump = hook_registry.bump,
+        has_one = admin @ AmmError::InvalidAuthority
+    )]
+    pub hook_registry: Account<'info, HookRegistry>,
+}
+
+impl<'info> UpdateHookRegistry<'info> {
+    pub fn approve_hook(&mut self, program_id: Pubkey, program_data_hash: Option<[u8; 32]>) -> Result<()> {
+        let registry = &mut self.hook_registry;
+        require!(registry.find(&program_id).is_none(), AmmError::HookAlreadyApproved);
+        require!(registry.hooks.len() < MAX_APPROVED_HOOKS, AmmError::HookRegistryFull);
+        registry.hooks.push(ApprovedHook { program_id, program_data_hash });
+        emit!(HookApproved { program_id, program_data_hash });
+        Ok(())
+    }
+
+    // Existing pools keep working; only new pools and bridge pools are checked
+    pub fn revoke_hook(&mut self, program_id: Pubkey) -> Result<()> {
+        let registry = &mut self.hook_registry;
+        let len = registry.hooks.len();
+        registry.hooks.retain(|hook| hook.program_id != program_id);
+        require!(registry.hooks.len() < len, AmmError::HookProgramNotApproved);
+        emit!(HookRevoked { program_id });
+        Ok(())
+    }
+
+    // Admin rotation is two-step like the pool authority: the proposed key has to sign
+    // `accept_hook_registry_admin`. A new proposal replaces any pending one.
+    pub fn propose_hook_registry_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        self.hook_registry.pending_admin = Some(new_admin);
+        emit!(HookRegistryAdminProposed {
+            admin: self.admin.key(),
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptHookRegistryAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+    #[account(mut, seeds = [b"hook-registry"], bump = hook_registry.bump)]
+    pub hook_registry: Account<'info, HookRegistry>,
+}
+
+impl<'info> AcceptHookRegistryAdmin<'info> {
+    pub fn accept_hook_registry_admin(&mut self) -> Result<()> {
+        let registry = &mut self.hook_registry;
+        let pending = registry.pending_admin.ok_or(AmmError::NoPendingAuthority)?;
+        require_keys_eq!(pending, self.pending_admin.key(), AmmError::InvalidPendingAuthority);
+        let previous_admin = registry.admin;
+        registry.admin = pending;
+        registry.pending_admin = None;
+        emit!(HookRegistryAdminAccepted {
+            previous_admin,
+            new_admin: pending,
+        });
+        Ok(())
+    }
+}
@@ -1,50 +1,64 @@
-use anchor_lang::{
-    prelude::*,
-    system_program::{create_account, CreateAccount},
-};
-use anchor_spl::{associated_token::AssociatedToken, token_2022::Token2022, token_interface::{Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount, TokenInterface}};
-use spl_tlv_account_resolution::{
-    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
-};
-use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
-use crate::config;
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount, TokenInterface}};
+use crate::{config, error::AmmError, mint_policy::{check_mint_extensions, check_transfer_hook}, HookRegistry, MAX_FEE_BPS};
 #[derive(Accounts)]
 #[instruction(seeds:u64)]
 pub struct Initialize<'info>{
 #[account(mut)]
     pub signer:Signer<'info>,
-      /// CHECK: ExtraAccountMetaList Account for storing transfer hook metadata
-      pub extra_account_meta_list:AccountInfo<'info>,
- pub mint:InterfaceAccount<'info, InterfaceMint>,
- /// CHECK: WSOL mint for Token-2022 (NATIVE_MINT_2022)
- pub wsol_mint: AccountInfo<'info>,
+ // Either side may be a legacy SPL mint or a Token-2022 mint, hooked or not
+ #[account(mint::token_program=token_program_a)]
+ pub mint_a:InterfaceAccount<'info, InterfaceMint>,
+ #[account(mint::token_program=token_program_b)]
+ pub mint_b:InterfaceAccount<'info, InterfaceMint>,
 #[account(init,seeds=[b"lp",config.key().as_ref()],bump,mint::decimals=6,mint::authority=config,payer=signer)]
 pub lp_token:InterfaceAccount<'info, InterfaceMint>,
-#[account(init,associated_token::mint=mint,associated_token::authority=config,payer=signer)]
-pub vault:InterfaceAccount<'info, InterfaceTokenAccount>,
-#[account(mut, seeds=[b"sol_vault", config.key().as_ref()], bump)]
-pub sol_vault:SystemAccount<'info>,
-/// CHECK: WSOL vault ATA for config authority - will be created if needed
-#[account(init_if_needed, associated_token::mint=wsol_mint, associated_token::authority=config, payer=signer)]
-pub wsol_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+#[account(init,associated_token::mint=mint_a,associated_token::authority=config,associated_token::token_program=token_program_a,payer=signer)]
+pub vault_a:InterfaceAccount<'info, InterfaceTokenAccount>,
+#[account(init,associated_token::mint=mint_b,associated_token::authority=config,associated_token::token_program=token_program_b,payer=signer)]
+pub vault_b:InterfaceAccount<'info, InterfaceTokenAccount>,
 #[account(init,seeds=[b"config",seeds.to_le_bytes().as_ref()],bump,payer=signer,space=8+config::INIT_SPACE)]
 pub config:Account<'info,config>,
+// Approved hook programs; hooked mints must point at one of them
+#[account(seeds=[b"hook-registry"],bump=hook_registry.bump)]
+pub hook_registry:Account<'info,HookRegistry>,
 pub system_program:Program<'info,System>,
+// Program of the LP mint
 pub token_program:Interface<'info,TokenInterface>,
+pub token_program_a:Interface<'info,TokenInterface>,
+pub token_program_b:Interface<'info,TokenInterface>,
 pub associated_token_program:Program<'info,AssociatedToken>
 }
 impl<'info>  Initialize <'info>{
-    pub fn initialize(&mut self,seed:u64,fee:u16,authority:Option<Pubkey>,bump:&InitializeBumps)->Result<()>{
+    // `allowed_extensions` is the creator's opt-in for EXT_RISKY bits found on either mint
+    // `remaining_accounts` carries the programdata of any hook approved with a build hash
+    pub fn initialize(&mut self,seed:u64,fee:u16,authority:Option<Pubkey>,allowed_extensions:u16,bump:&InitializeBumps,remaining_accounts:&[AccountInfo])->Result<()>{
+        require!(fee < MAX_FEE_BPS, AmmError::InvalidFee);
+        require_keys_neq!(self.mint_a.key(), self.mint_b.key(), AmmError::IdenticalMints);
+        let extensions_a=check_mint_extensions(&self.mint_a.to_account_info(),allowed_extensions)?;
+        let extensions_b=check_mint_extensions(&self.mint_b.to_account_info(),allowed_extensions)?;
+        check_transfer_hook(&self.mint_a.to_account_info(),&self.hook_registry,remaining_accounts)?;
+        check_transfer_hook(&self.mint_b.to_account_info(),&self.hook_registry,remaining_accounts)?;
         self.config.set_inner(config { 
             seed, 
             authority, 
-            mint:self.mint.key(), 
+            pending_authority: None,
+            mint_a:self.mint_a.key(),
+            mint_b:self.mint_b.key(),
+            token_program_a:self.token_program_a.key(),
+            token_program_b:self.token_program_b.key(),
+            extensions_a,
+            extensions_b,
             fee, 
-            locked:false, 
+            protocol_fee_share:0,
+            protocol_fees_a:0,
+            protocol_fees_b:0,
+            reserve_a:0,
+            reserve_b:0,
+            paused:0, 
+            guardian:None,
             config_bump:bump.config, 
             lp_bump:bump.lp_token,
-            wsol_mint:self.wsol_mint.key(),
-            sol_vault_bump:bump.sol_vault,
             bridge_config: None,  // No bridge initially
             is_bridge_pool: false, // Standard pool by default
         });
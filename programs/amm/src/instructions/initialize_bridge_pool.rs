@@ -5,6 +5,7 @@ use anchor_spl::{
 };
 use crate::state::*;
 use crate::error::*;
+use crate::mint_policy::check_transfer_hook;
 
 #[derive(Accounts)]
 pub struct InitializeBridgePool<'info> {
@@ -33,6 +34,10 @@ pub struct InitializeBridgePool<'info> {
     
     // Token-2022 mint that will be bridged
     pub restricted_token_mint: InterfaceAccount<'info, Mint>,
+
+    // Approved hook programs; the restricted mint's hook must be one of them
+    #[account(seeds = [b"hook-registry"], bump = hook_registry.bump)]
+    pub hook_registry: Account<'info, HookRegistry>,
     
     // Bridge token mint (standard SPL) for pool trading
     #[account(
@@ -51,6 +56,12 @@ pub struct InitializeBridgePool<'info> {
 }
 
 pub fn initialize_bridge_pool(ctx: Context<InitializeBridgePool>) -> Result<()> {
+    check_transfer_hook(
+        &ctx.accounts.restricted_token_mint.to_account_info(),
+        &ctx.accounts.hook_registry,
+        ctx.remaining_accounts,
+    )?;
+
     let amm_config = &mut ctx.accounts.amm_config;
     let bridge_pool_config = &mut ctx.accounts.bridge_pool_config;
     
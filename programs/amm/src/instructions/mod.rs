@@ -6,6 +6,20 @@ pub mod swap;
 pub use swap::*;
 pub mod withdraw;
 pub use withdraw::*;
+pub mod update_config;
+pub use update_config::*;
+pub mod accept_authority;
+pub use accept_authority::*;
+pub mod pause;
+pub use pause::*;
+pub mod collect_protocol_fees;
+pub use collect_protocol_fees::*;
+pub mod sync;
+pub use sync::*;
+pub mod skim;
+pub use skim::*;
+pub mod hook_registry;
+pub use hook_registry::*;
 
 pub mod  initialize_list;
 pub  use initialize_list::*;
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{config, error::AmmError, events::PauseFlagsUpdated, PAUSE_ALL};
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    // Either the pool authority or the guardian
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, config>,
+}
+
+impl<'info> Pause<'info> {
+    pub fn pause(&mut self, flags: u8) -> Result<()> {
+        let signer = self.signer.key();
+        require!(
+            self.config.authority == Some(signer) || self.config.guardian == Some(signer),
+            AmmError::InvalidAuthority
+        );
+        require!(flags != 0 && flags & !PAUSE_ALL == 0, AmmError::InvalidPauseFlags);
+
+        self.config.paused |= flags;
+        emit!(PauseFlagsUpdated {
+            config: self.config.key(),
+            signer,
+            paused: self.config.paused,
+        });
+        Ok(())
+    }
+}
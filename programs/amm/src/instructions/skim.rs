@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{config, error::AmmError, events::ReservesSkimmed, token_transfer::transfer_checked_with_hook};
+
+#[derive(Accounts)]
+pub struct SkimReserves<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, config>,
+    #[account(address = config.mint_a @ AmmError::InvalidToken, mint::token_program = token_program_a)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(address = config.mint_b @ AmmError::InvalidToken, mint::token_program = token_program_b)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Owner of the recipient token accounts; only recorded in the event
+    pub recipient: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint_a, token::authority = recipient, token::token_program = token_program_a)]
+    pub recipient_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint_b, token::authority = recipient, token::token_program = token_program_b)]
+    pub recipient_b: InterfaceAccount<'info, TokenAccount>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SkimReserves<'info> {
+    // Sweeps whatever the vaults hold beyond reserves and accrued protocol fees
+    pub fn skim(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+
+        let excess_a = self
+            .vault_a
+            .amount
+            .saturating_sub(self.config.reserve_a)
+            .saturating_sub(self.config.protocol_fees_a);
+        let excess_b = self
+            .vault_b
+            .amount
+            .saturating_sub(self.config.reserve_b)
+            .saturating_sub(self.config.protocol_fees_b);
+
+        let seeds = &[
+            b"config".as_ref(),
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        if excess_a != 0 {
+            transfer_checked_with_hook(
+                &self.token_program_a.to_account_info(),
+                &self.vault_a.to_account_info(),
+                &self.mint_a.to_account_info(),
+                &self.recipient_a.to_account_info(),
+                &self.config.to_account_info(),
+                remaining_accounts,
+                excess_a,
+                self.mint_a.decimals,
+                signer_seeds,
+            )?;
+        }
+        if excess_b != 0 {
+            transfer_checked_with_hook(
+                &self.token_program_b.to_account_info(),
+                &self.vault_b.to_account_info(),
+                &self.mint_b.to_account_info(),
+                &self.recipient_b.to_account_info(),
+                &self.config.to_account_info(),
+                remaining_accounts,
+                excess_b,
+                self.mint_b.decimals,
+                signer_seeds,
+            )?;
+        }
+
+        emit!(ReservesSkimmed {
+            config: self.config.key(),
+            recipient: self.recipient.key(),
+            amount_a: excess_a,
+            amount_b: excess_b,
+        });
+        Ok(())
+    }
+}
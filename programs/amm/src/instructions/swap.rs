@@ -1,151 +1,169 @@
 use anchor_lang::{prelude::*};
-use anchor_spl::{associated_token::AssociatedToken, token::{ transfer, transfer_checked, Mint, MintTo, Token, TokenAccount, Transfer, TransferChecked}, token_2022::Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use constant_product_curve::{ConstantProduct, LiquidityPair};
 
-use crate::{config, error::AmmError};
+use crate::{config, error::AmmError, math::amount_in_for_exact_out, token_transfer::{amount_after_transfer_fee, amount_before_transfer_fee, is_native_mint, transfer_checked_with_hook, unwrap_if_native, wrap_if_native}, PAUSE_SWAP};
 
 #[derive(Accounts)]
 #[instruction(seeds:u64)]
 pub struct Swap<'info>{
 #[account(mut)]
 pub signer:Signer<'info>,
-pub token_mint: Account<'info, Mint>,  
-    /// CHECK: WSOL mint for Token-2022 (NATIVE_MINT_2022)
-    // wsol_mint accessed via remaining_accounts to bypass Anchor validation
-    #[account(mut)]
-    pub user_token: Account<'info, TokenAccount>,  // User's Token-2022 accoun             // User's SOL account
-    #[account(mut)]
-    pub user_wsol: Account<'info, TokenAccount>,
-#[account(mut,seeds=[b"lp",config.key().as_ref()],bump=config.lp_bump)]
-pub lp_token:Account<'info,Mint>,
-#[account(mut, associated_token::mint=token_mint, associated_token::authority=config)]
-pub token_vault: Account<'info, TokenAccount>,
-#[account(mut, seeds=[b"sol_vault", config.key().as_ref()], bump=config.sol_vault_bump)]
-    /// CHECK: This is the user's SOL account, checked in the instruction logic.
-pub sol_vault: AccountInfo<'info>,
+#[account(address=config.mint_a @ AmmError::InvalidToken, mint::token_program=token_program_a)]
+pub mint_a: InterfaceAccount<'info, Mint>,
+#[account(address=config.mint_b @ AmmError::InvalidToken, mint::token_program=token_program_b)]
+pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint=mint_a, token::authority=signer, token::token_program=token_program_a, constraint=!is_native_mint(&mint_a.key()) || user_a.amount==0 @ AmmError::NativeAccountNotEmpty)]
+    pub user_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint=mint_b, token::authority=signer, token::token_program=token_program_b, constraint=!is_native_mint(&mint_b.key()) || user_b.amount==0 @ AmmError::NativeAccountNotEmpty)]
+    pub user_b: InterfaceAccount<'info, TokenAccount>,
+#[account(seeds=[b"lp",config.key().as_ref()],bump=config.lp_bump)]
+pub lp_token:InterfaceAccount<'info,Mint>,
+#[account(mut, associated_token::mint=mint_a, associated_token::authority=config, associated_token::token_program=token_program_a)]
+pub vault_a: InterfaceAccount<'info, TokenAccount>,
+#[account(mut, associated_token::mint=mint_b, associated_token::authority=config, associated_token::token_program=token_program_b)]
+pub vault_b: InterfaceAccount<'info, TokenAccount>,
 #[account(mut,seeds=[b"config",config.seed.to_le_bytes().as_ref()],bump=config.config_bump)]
 pub config:Account<'info,config>,
-    /// CHECK: ExtraAccountMetaList passed through; validated at runtime by transfer hook
-pub extra_account_meta_list: UncheckedAccount<'info>,
-    /// CHECK: WSOL vault ATA for config authority
-    #[account(mut)]
-    pub wsol_vault: AccountInfo<'info>,
 pub system_program:Program<'info,System>,
-pub token_program:Program<'info,Token>,
-pub token_2022_program: Program<'info, Token2022>,
-pub associated_token_program:Program<'info,AssociatedToken>
+// Owning programs of each side; hook accounts for both sides come in via remaining_accounts
+pub token_program_a:Interface<'info,TokenInterface>,
+pub token_program_b:Interface<'info,TokenInterface>,
 }
 impl<'info>  Swap <'info>{
-    pub fn swap(&mut self, amount: u64, is_sol_to_token: bool, min_receive: u64) -> Result<()> {
-        require!(self.config.locked == false, AmmError::PoolLocked);
+    pub fn swap(&mut self, amount: u64, is_a_to_b: bool, min_receive: u64, expiration: i64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_SWAP), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
         require!(amount != 0, AmmError::InvalidAmount);
-    
-        let sol_balance = self.sol_vault.lamports();
-        let token_balance = self.token_vault.amount;
-        
+
+        // Curve math runs on what actually lands in and leaves the vaults, net of Token-2022 transfer fees
+        let (mint_in, mint_out) = self.mints(is_a_to_b);
+        let amount_in = amount_after_transfer_fee(&mint_in, amount)?;
+        require!(amount_in != 0, AmmError::InvalidAmount);
+
         let mut curve = ConstantProduct::init(
-            sol_balance, 
-            token_balance, 
-            self.lp_token.supply, 
-            self.config.fee, 
+            self.config.reserve_a,
+            self.config.reserve_b,
+            self.lp_token.supply,
+            self.config.fee,
             None
         ).map_err(|_| AmmError::CurveError)?;
-        
-        let swap_direction = if is_sol_to_token {
-            LiquidityPair::X  // SOL is X
+
+        let swap_direction = if is_a_to_b {
+            LiquidityPair::X  // A is X
         } else {
-            LiquidityPair::Y  // Token is Y
+            LiquidityPair::Y  // B is Y
         };
-        
-        let swap_result = curve.swap(swap_direction, amount, min_receive)
+
+        let swap_result = curve.swap(swap_direction, amount_in, 0)
             .map_err(|_| AmmError::CurveError)?;
-        
+
         require!(swap_result.deposit != 0 || swap_result.withdraw != 0, AmmError::InvalidAmount);
-        
-        if is_sol_to_token {
-    
-            self.transfer_sol_to_vault(swap_result.deposit)?;
-            self.transfer_token_from_vault(swap_result.withdraw)?;
-        } else {
-        
-            self.transfer_token_to_vault(swap_result.deposit)?;
-            self.transfer_sol_from_vault(swap_result.withdraw)?;
-        }    
-        Ok(())
-    }
-    pub fn transfer_sol_from_vault(&self, amount: u64) -> Result<()> {
-        let transfer_instruction = anchor_lang::system_program::Transfer {
-            from: self.sol_vault.to_account_info(),
-            to: self.signer.to_account_info(),
-        };
-        let seed_config=&self.config.key();
-        let seeds = &[
-            b"sol_vault".as_ref(),
-            &seed_config.as_ref(),
-            &[self.config.sol_vault_bump]
-        ];
-        let signer_seeds = &[&seeds[..]];
-        
-        let cpi_context = CpiContext::new_with_signer(
-            self.system_program.to_account_info(),
-            transfer_instruction,
-            signer_seeds,
-        );
-        
-        anchor_lang::system_program::transfer(cpi_context, amount)
+        let received = amount_after_transfer_fee(&mint_out, swap_result.withdraw)?;
+        require!(received >= min_receive, AmmError::SlippageExceded);
+        let protocol_cut = self.config.accrue_protocol_fee(is_a_to_b, swap_result.fee)?;
+        self.update_reserves(is_a_to_b, swap_result.deposit - protocol_cut, swap_result.withdraw)?;
+
+        self.transfer_to_vault(is_a_to_b, amount, remaining_accounts)?;
+        self.transfer_from_vault(!is_a_to_b, swap_result.withdraw, remaining_accounts)?;
+        self.unwrap_user_tokens()
     }
+    // `amount_out` is what the user receives; `max_in` bounds what leaves their wallet
+    pub fn swap_exact_out(&mut self, amount_out: u64, max_in: u64, is_a_to_b: bool, expiration: i64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(!self.config.is_paused(PAUSE_SWAP), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
 
+        let (reserve_in, reserve_out) = if is_a_to_b {
+            (self.config.reserve_a, self.config.reserve_b)
+        } else {
+            (self.config.reserve_b, self.config.reserve_a)
+        };
 
+        let (mint_in, mint_out) = self.mints(is_a_to_b);
+        let vault_out = amount_before_transfer_fee(&mint_out, amount_out)?;
+        let (amount_in, fee_amount) = amount_in_for_exact_out(reserve_in, reserve_out, vault_out, self.config.fee)?;
+        let user_in = amount_before_transfer_fee(&mint_in, amount_in)?;
+        require!(user_in <= max_in, AmmError::SlippageExceded);
+        let protocol_cut = self.config.accrue_protocol_fee(is_a_to_b, fee_amount)?;
+        self.update_reserves(is_a_to_b, amount_in - protocol_cut, vault_out)?;
 
-    pub fn transfer_sol_to_vault(&self, amount: u64) -> Result<()> {
-        let transfer_instruction = anchor_lang::system_program::Transfer {
-            from: self.signer.to_account_info(),
-            to: self.sol_vault.to_account_info(),
+        self.transfer_to_vault(is_a_to_b, user_in, remaining_accounts)?;
+        self.transfer_from_vault(!is_a_to_b, vault_out, remaining_accounts)?;
+        self.unwrap_user_tokens()
+    }
+    pub fn mints(&self, is_a_to_b: bool) -> (AccountInfo<'info>, AccountInfo<'info>) {
+        let (mint_a, mint_b) = (self.mint_a.to_account_info(), self.mint_b.to_account_info());
+        if is_a_to_b { (mint_a, mint_b) } else { (mint_b, mint_a) }
+    }
+    pub fn update_reserves(&mut self, is_a_to_b: bool, amount_in: u64, amount_out: u64) -> Result<()> {
+        let config = &mut *self.config;
+        let (reserve_in, reserve_out) = if is_a_to_b {
+            (&mut config.reserve_a, &mut config.reserve_b)
+        } else {
+            (&mut config.reserve_b, &mut config.reserve_a)
         };
-        
-        let cpi_context = CpiContext::new(
-            self.system_program.to_account_info(),
-            transfer_instruction,
-        );
-        
-        anchor_lang::system_program::transfer(cpi_context, amount)
+        *reserve_in = reserve_in.checked_add(amount_in).ok_or(AmmError::Overflow)?;
+        *reserve_out = reserve_out.checked_sub(amount_out).ok_or(AmmError::Underflow)?;
+        Ok(())
     }
-    pub fn transfer_token_to_vault(&self, amount: u64) -> Result<()> {
-        // Use Token-2022 program for transfer with hooks
-        let transfer_instruction = TransferChecked {
-            from: self.user_token.to_account_info(),
-            mint: self.token_mint.to_account_info(),
-            to: self.token_vault.to_account_info(),
-            authority: self.signer.to_account_info(),
-        }; 
-        let cpi_context = CpiContext::new(
-            self.token_2022_program.to_account_info(),
-            transfer_instruction,
-        );
-        transfer_checked(cpi_context, amount, self.token_mint.decimals)
+    pub fn transfer_to_vault(&self, is_a: bool, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let (token_program, from, mint, to) = if is_a {
+            (&self.token_program_a, &self.user_a, &self.mint_a, &self.vault_a)
+        } else {
+            (&self.token_program_b, &self.user_b, &self.mint_b, &self.vault_b)
+        };
+        wrap_if_native(
+            &mint.key(),
+            &self.system_program.to_account_info(),
+            &token_program.to_account_info(),
+            &self.signer.to_account_info(),
+            &from.to_account_info(),
+            amount,
+        )?;
+        transfer_checked_with_hook(
+            &token_program.to_account_info(),
+            &from.to_account_info(),
+            &mint.to_account_info(),
+            &to.to_account_info(),
+            &self.signer.to_account_info(),
+            remaining_accounts,
+            amount,
+            mint.decimals,
+            &[],
+        )
     }
-    pub fn transfer_token_from_vault(&self, amount: u64) -> Result<()> {
-        let transfer_instruction = Transfer {
-            from: self.token_vault.to_account_info(),
-            to: self.user_token.to_account_info(),
-            authority: self.config.to_account_info(),
+    pub fn transfer_from_vault(&self, is_a: bool, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let (token_program, from, mint, to) = if is_a {
+            (&self.token_program_a, &self.vault_a, &self.mint_a, &self.user_a)
+        } else {
+            (&self.token_program_b, &self.vault_b, &self.mint_b, &self.user_b)
         };
-        
         let seeds = &[
             b"config".as_ref(),
             &self.config.seed.to_le_bytes(),
             &[self.config.config_bump]
         ];
         let signer_seeds = &[&seeds[..]];
-        
-        let cpi_context = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            transfer_instruction,
+
+        transfer_checked_with_hook(
+            &token_program.to_account_info(),
+            &from.to_account_info(),
+            &mint.to_account_info(),
+            &to.to_account_info(),
+            &self.config.to_account_info(),
+            remaining_accounts,
+            amount,
+            mint.decimals,
             signer_seeds,
-        );
-        
-        transfer(cpi_context, amount)
+        )
     }
-    
-
-}
\ No newline at end of file
+    pub fn unwrap_user_tokens(&self) -> Result<()> {
+        for (token_program, account, mint) in [
+            (&self.token_program_a, &self.user_a, &self.mint_a),
+            (&self.token_program_b, &self.user_b, &self.mint_b),
+        ] {
+            unwrap_if_native(&mint.key(), &token_program.to_account_info(), &self.signer.to_account_info(), &account.to_account_info())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{config, error::AmmError, events::ReservesSynced};
+
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, config>,
+    #[account(seeds = [b"lp", config.key().as_ref()], bump = config.lp_bump)]
+    pub lp_token: InterfaceAccount<'info, Mint>,
+    #[account(address = config.mint_a @ AmmError::InvalidToken, mint::token_program = token_program_a)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(address = config.mint_b @ AmmError::InvalidToken, mint::token_program = token_program_b)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_a
+    )]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        associated_token::mint = mint_b,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_b
+    )]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SyncReserves<'info> {
+    pub fn sync(&mut self) -> Result<()> {
+        // Before the first deposit a donation would leave non-zero reserves with no LP
+        // supply, and every deposit path would then refuse the pool
+        require!(self.lp_token.supply != 0, AmmError::NoLiquidityInPool);
+        self.config.reserve_a = self
+            .vault_a
+            .amount
+            .checked_sub(self.config.protocol_fees_a)
+            .ok_or(AmmError::Underflow)?;
+        self.config.reserve_b = self
+            .vault_b
+            .amount
+            .checked_sub(self.config.protocol_fees_b)
+            .ok_or(AmmError::Underflow)?;
+
+        emit!(ReservesSynced {
+            config: self.config.key(),
+            reserve_a: self.config.reserve_a,
+            reserve_b: self.config.reserve_b,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    config,
+    error::AmmError,
+    events::{AuthorityTransferCancelled, AuthorityTransferProposed, PauseFlagsUpdated},
+    MAX_FEE_BPS, PAUSE_ALL,
+};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_fee(&mut self, fee: u16) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        require!(fee < MAX_FEE_BPS, AmmError::InvalidFee);
+        self.config.fee = fee;
+        msg!("Pool fee updated to {} bps", fee);
+        Ok(())
+    }
+
+    // Share of each swap fee, in basis points of the fee, accrued to the protocol
+    pub fn set_protocol_fee_share(&mut self, protocol_fee_share: u16) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        require!(protocol_fee_share <= MAX_FEE_BPS, AmmError::InvalidFee);
+        self.config.protocol_fee_share = protocol_fee_share;
+        msg!("Protocol fee share updated to {} bps", protocol_fee_share);
+        Ok(())
+    }
+
+    // Only the authority can lift a pause; the guardian is limited to `pause`
+    pub fn unpause(&mut self, flags: u8) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        require!(flags & !PAUSE_ALL == 0, AmmError::InvalidPauseFlags);
+        self.config.paused &= !flags;
+        emit!(PauseFlagsUpdated {
+            config: self.config.key(),
+            signer: self.authority.key(),
+            paused: self.config.paused,
+        });
+        Ok(())
+    }
+
+    pub fn set_guardian(&mut self, guardian: Option<Pubkey>) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        self.config.guardian = guardian;
+        match guardian {
+            Some(key) => msg!("Pool guardian set to {}", key),
+            None => msg!("Pool guardian removed"),
+        }
+        Ok(())
+    }
+
+    // Authority rotation is two-step: the proposed key has to sign `accept_authority`
+    pub fn propose_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        self.config.pending_authority = Some(new_authority);
+        emit!(AuthorityTransferProposed {
+            config: self.config.key(),
+            authority: self.authority.key(),
+            pending_authority: new_authority,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_authority_transfer(&mut self) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        let cancelled_authority = self
+            .config
+            .pending_authority
+            .take()
+            .ok_or(AmmError::NoPendingAuthority)?;
+        emit!(AuthorityTransferCancelled {
+            config: self.config.key(),
+            authority: self.authority.key(),
+            cancelled_authority,
+        });
+        Ok(())
+    }
+
+    // Renouncing makes the config immutable and drops any pending transfer
+    pub fn renounce_authority(&mut self) -> Result<()> {
+        self.config.assert_authority(&self.authority.key())?;
+        self.config.authority = None;
+        self.config.pending_authority = None;
+        msg!("Pool authority renounced");
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
-use anchor_lang::{prelude::*};
-use anchor_spl::{associated_token::AssociatedToken, token::{burn, mint_to, transfer, transfer_checked, Burn, Mint, MintTo, Token, TokenAccount, Transfer}};
-use constant_product_curve::ConstantProduct;
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface}};
+use constant_product_curve::{ConstantProduct, LiquidityPair};
 
-use crate::{config, error::AmmError};
+use crate::{config, error::AmmError, token_transfer::{amount_after_transfer_fee, is_native_mint, transfer_checked_with_hook, unwrap_if_native}, PAUSE_SWAP, PAUSE_WITHDRAW};
 
 
 #[derive(Accounts)]
@@ -10,42 +10,96 @@ use crate::{config, error::AmmError};
 pub struct Withdraw<'info>{
   #[account(mut)]
   pub signer:Signer<'info>,
-      
-  pub mintx:Account<'info,Mint>,
-  pub minty:Account<'info,Mint>,
-  #[account(mut)]
-  pub user_x:Account<'info,TokenAccount>,
-  #[account(mut)]
-  pub user_y:Account<'info,TokenAccount>,
-  #[account(init_if_needed,associated_token::mint=lp_token,associated_token::authority=signer,payer=signer)]
-  pub user_lp:Account<'info,TokenAccount>,
+
+  #[account(address=config.mint_a @ AmmError::InvalidToken, mint::token_program=token_program_a)]
+  pub mint_a:InterfaceAccount<'info,Mint>,
+  #[account(address=config.mint_b @ AmmError::InvalidToken, mint::token_program=token_program_b)]
+  pub mint_b:InterfaceAccount<'info,Mint>,
+  #[account(mut, token::mint=mint_a, token::authority=signer, token::token_program=token_program_a, constraint=!is_native_mint(&mint_a.key()) || user_a.amount==0 @ AmmError::NativeAccountNotEmpty)]
+  pub user_a:InterfaceAccount<'info,TokenAccount>,
+  #[account(mut, token::mint=mint_b, token::authority=signer, token::token_program=token_program_b, constraint=!is_native_mint(&mint_b.key()) || user_b.amount==0 @ AmmError::NativeAccountNotEmpty)]
+  pub user_b:InterfaceAccount<'info,TokenAccount>,
+  #[account(init_if_needed,associated_token::mint=lp_token,associated_token::authority=signer,associated_token::token_program=token_program,payer=signer)]
+  pub user_lp:InterfaceAccount<'info,TokenAccount>,
   #[account(mut,seeds=[b"lp",config.key().as_ref()],bump=config.lp_bump)]
-  pub lp_token:Account<'info,Mint>,
-  #[account(mut,associated_token::mint=mintx,associated_token::authority=config)]
-  pub vault_x:Account<'info,TokenAccount>,
-  #[account(mut,associated_token::mint=minty,associated_token::authority=config)]
-  pub vault_y:Account<'info,TokenAccount>,
+  pub lp_token:InterfaceAccount<'info,Mint>,
+  #[account(mut,associated_token::mint=mint_a,associated_token::authority=config,associated_token::token_program=token_program_a)]
+  pub vault_a:InterfaceAccount<'info,TokenAccount>,
+  #[account(mut,associated_token::mint=mint_b,associated_token::authority=config,associated_token::token_program=token_program_b)]
+  pub vault_b:InterfaceAccount<'info,TokenAccount>,
   #[account(mut,seeds=[b"config",config.seed.to_le_bytes().as_ref()],bump=config.config_bump)]
   pub config:Account<'info,config>,
   pub system_program:Program<'info,System>,
-  pub token_program:Program<'info,Token>,
+  // Program of the LP mint
+  pub token_program:Interface<'info,TokenInterface>,
+  // Owning programs of each side; hook accounts for payouts come in via remaining_accounts
+  pub token_program_a:Interface<'info,TokenInterface>,
+  pub token_program_b:Interface<'info,TokenInterface>,
   pub associated_token_program:Program<'info,AssociatedToken>
 }
 impl<'info>  Withdraw <'info>{
-    pub fn withdraw(&mut self,amount:u64,min_x:u64,min_y:u64)->Result<()>{
-        require!(self.config.locked == false, AmmError::PoolLocked);
+    pub fn withdraw(&mut self,amount:u64,min_a:u64,min_b:u64,expiration:i64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
        require!(amount!=0,AmmError::InvalidAmount);
-     let amounts=ConstantProduct::xy_withdraw_amounts_from_l(self.vault_x.amount, self.vault_y.amount, self.lp_token.supply, amount, 6).map_err(|_|AmmError::CurveError)?;
-       require!(amounts.x>=min_x && amounts.y>=min_y,AmmError::SlippageExceded);
-       self.withdrawtoken(true, amounts.x)?;
-       self.withdrawtoken(false,amounts.y)?;
+     let (reserve_a,reserve_b)=(self.config.reserve_a,self.config.reserve_b);
+     let amounts=ConstantProduct::xy_withdraw_amounts_from_l(reserve_a, reserve_b, self.lp_token.supply, amount, 6).map_err(|_|AmmError::CurveError)?;
+       // Minimums apply to what the user receives after any Token-2022 transfer fee
+       require!(self.net_out(true,amounts.x)?>=min_a && self.net_out(false,amounts.y)?>=min_b,AmmError::SlippageExceded);
+       self.withdraw_token(true, amounts.x, remaining_accounts)?;
+       self.withdraw_token(false,amounts.y, remaining_accounts)?;
+       self.config.reserve_a=reserve_a.checked_sub(amounts.x).ok_or(AmmError::Underflow)?;
+       self.config.reserve_b=reserve_b.checked_sub(amounts.y).ok_or(AmmError::Underflow)?;
+       self.unwrap_user_token(true)?;
+       self.unwrap_user_token(false)?;
        self.burn(amount)
-    
+
     }
-    pub fn withdrawtoken(&self,is_x:bool,amount:u64)->Result<()>{
-      let (to,from)=match is_x {
-          true=>(&self.user_x,&self.vault_x),
-          false=>(&self.user_y,&self.vault_y)
+    // Burns LP and pays the whole position in one asset: the other leg is swapped through
+    // the curve against post-withdrawal reserves and never leaves the vault.
+    pub fn withdraw_single_sided(&mut self,amount:u64,receive_a:bool,min_out:u64,expiration:i64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+        require!(!self.config.is_paused(PAUSE_WITHDRAW), AmmError::PoolLocked);
+        require!(!self.config.is_paused(PAUSE_SWAP), AmmError::PoolLocked);
+        require!(Clock::get()?.unix_timestamp <= expiration, AmmError::OfferExpired);
+        require!(amount!=0,AmmError::InvalidAmount);
+        let supply=self.lp_token.supply;
+        let amounts=ConstantProduct::xy_withdraw_amounts_from_l(self.config.reserve_a, self.config.reserve_b, supply, amount, 6).map_err(|_|AmmError::CurveError)?;
+        let mut reserve_a=self.config.reserve_a.checked_sub(amounts.x).ok_or(AmmError::Underflow)?;
+        let mut reserve_b=self.config.reserve_b.checked_sub(amounts.y).ok_or(AmmError::Underflow)?;
+
+        let (kept,swap_in)=if receive_a {(amounts.x,amounts.y)} else {(amounts.y,amounts.x)};
+        let mut swap_out=0;
+        if swap_in!=0 {
+            let remaining_supply=supply.checked_sub(amount).ok_or(AmmError::Underflow)?;
+            let mut curve=ConstantProduct::init(reserve_a,reserve_b,remaining_supply,self.config.fee,None)
+                .map_err(|_| AmmError::CurveError)?;
+            let pair=if receive_a {LiquidityPair::Y} else {LiquidityPair::X};
+            let swap_result=curve.swap(pair,swap_in,0).map_err(|_| AmmError::CurveError)?;
+            let protocol_cut=self.config.accrue_protocol_fee(!receive_a,swap_result.fee)?;
+            let (reserve_in,reserve_out)=if receive_a {(&mut reserve_b,&mut reserve_a)} else {(&mut reserve_a,&mut reserve_b)};
+            *reserve_in=reserve_in.checked_add(swap_in-protocol_cut).ok_or(AmmError::Overflow)?;
+            *reserve_out=reserve_out.checked_sub(swap_result.withdraw).ok_or(AmmError::Underflow)?;
+            swap_out=swap_result.withdraw;
+        }
+
+        let amount_out=kept.checked_add(swap_out).ok_or(AmmError::Overflow)?;
+        require!(amount_out!=0,AmmError::InvalidAmount);
+        require!(self.net_out(receive_a,amount_out)?>=min_out,AmmError::SlippageExceded);
+        self.withdraw_token(receive_a,amount_out,remaining_accounts)?;
+        self.config.reserve_a=reserve_a;
+        self.config.reserve_b=reserve_b;
+        self.unwrap_user_token(receive_a)?;
+        self.burn(amount)
+    }
+    pub fn net_out(&self,is_a:bool,amount:u64)->Result<u64>{
+      let mint=if is_a {self.mint_a.to_account_info()} else {self.mint_b.to_account_info()};
+      amount_after_transfer_fee(&mint,amount)
+    }
+    pub fn withdraw_token(&self,is_a:bool,amount:u64,remaining_accounts:&[AccountInfo<'info>])->Result<()>{
+      let (token_program,from,mint,to)=if is_a {
+          (&self.token_program_a,&self.vault_a,&self.mint_a,&self.user_a)
+      } else {
+          (&self.token_program_b,&self.vault_b,&self.mint_b,&self.user_b)
       };
       let seeds = &[
         b"config".as_ref(),
@@ -53,18 +107,29 @@ impl<'info>  Withdraw <'info>{
         &[self.config.config_bump]
     ];
     let signer_seeds = &[&seeds[..]];
-      let account=Transfer{
-        from:from.to_account_info(),
-        to:to.to_account_info(),
-        authority:self.config.to_account_info()
+      transfer_checked_with_hook(
+        &token_program.to_account_info(),
+        &from.to_account_info(),
+        &mint.to_account_info(),
+        &to.to_account_info(),
+        &self.config.to_account_info(),
+        remaining_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+      )
+    }
+    pub fn unwrap_user_token(&self,is_a:bool)->Result<()>{
+      let (token_program,account,mint)=if is_a {
+          (&self.token_program_a,&self.user_a,&self.mint_a)
+      } else {
+          (&self.token_program_b,&self.user_b,&self.mint_b)
       };
-     let cpi_context=CpiContext::new_with_signer(self.token_program.to_account_info(), account,signer_seeds);
-      transfer(cpi_context, amount)
-
+      unwrap_if_native(&mint.key(),&token_program.to_account_info(),&self.signer.to_account_info(),&account.to_account_info())
     }
     pub fn burn(&self,amount:u64)->Result<()>{
         let mint=Burn{mint:self.lp_token.to_account_info(),from:self.user_lp.to_account_info(),authority:self.signer.to_account_info()};
         let cpi_context=CpiContext::new(self.token_program.to_account_info(),mint);
        burn(cpi_context, amount)
     }
-}
\ No newline at end of file
+}
@@ -12,6 +12,11 @@ pub mod state;
 pub use state::*;
 pub mod error;
 pub use error::*;
+pub mod events;
+pub use events::*;
+pub mod math;
+pub mod mint_policy;
+pub mod token_transfer;
 
 #[program]
 pub mod amm {
@@ -21,39 +26,156 @@ pub mod amm {
         ctx: Context<Initialize>,
         seed: u64,
         fee: u16,
-        authority: Option<Pubkey>
+        authority: Option<Pubkey>,
+        allowed_extensions: u16
     ) -> Result<()> {
-        ctx.accounts.initialize(seed, fee, authority, &ctx.bumps)
+        ctx.accounts.initialize(seed, fee, authority, allowed_extensions, &ctx.bumps, ctx.remaining_accounts)
+    }
+    pub fn initialize_hook_registry(ctx: Context<InitializeHookRegistry>, admin: Pubkey) -> Result<()> {
+        ctx.accounts.initialize_hook_registry(admin, ctx.bumps.hook_registry)
+    }
+    pub fn approve_hook(
+        ctx: Context<UpdateHookRegistry>,
+        program_id: Pubkey,
+        program_data_hash: Option<[u8; 32]>
+    ) -> Result<()> {
+        ctx.accounts.approve_hook(program_id, program_data_hash)
+    }
+    pub fn revoke_hook(ctx: Context<UpdateHookRegistry>, program_id: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_hook(program_id)
+    }
+    pub fn propose_hook_registry_admin(ctx: Context<UpdateHookRegistry>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.propose_hook_registry_admin(new_admin)
+    }
+    pub fn accept_hook_registry_admin(ctx: Context<AcceptHookRegistryAdmin>) -> Result<()> {
+        ctx.accounts.accept_hook_registry_admin()
     }
     pub fn initialize_extra_account_meta_list(
         ctx: Context<InitializeExtraAccountMetaList>,
     ) -> Result<()> {
         ctx.accounts.initialize(ctx.bumps,*ctx.program_id)
     }
-    pub fn deposit(
-        ctx: Context<Deposit>,
-        sol_amount: u64, token_amount: u64, max_sol: u64, max_token: u64
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        amount: u64,
+        max_a: u64,
+        max_b: u64,
+        expiration: i64
+    ) -> Result<()> {
+        ctx.accounts.deposit(amount, max_a, max_b, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn deposit_by_amounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        desired_a: u64,
+        desired_b: u64,
+        min_lp_out: u64,
+        expiration: i64
     ) -> Result<()> {
-        ctx.accounts.deposit(token_amount,max_sol,max_token)
+        ctx.accounts.deposit_by_amounts(desired_a, desired_b, min_lp_out, expiration, ctx.remaining_accounts)
     }
 
-    pub fn swap(
-        ctx: Context<Swap>,
+    pub fn deposit_single_sided<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        is_a: bool,
+        amount_in: u64,
+        min_lp_out: u64,
+        expiration: i64
+    ) -> Result<()> {
+        ctx.accounts.deposit_single_sided(is_a, amount_in, min_lp_out, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
         amount: u64,
-        is_x: bool,
-        min_receive: u64
+        is_a_to_b: bool,
+        min_receive: u64,
+        expiration: i64
+    ) -> Result<()> {
+        ctx.accounts.swap(amount, is_a_to_b, min_receive, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn swap_exact_out<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount_out: u64,
+        max_in: u64,
+        is_a_to_b: bool,
+        expiration: i64
     ) -> Result<()> {
-        ctx.accounts.swap(amount, is_x, min_receive)
+        ctx.accounts.swap_exact_out(amount_out, max_in, is_a_to_b, expiration, ctx.remaining_accounts)
     }
  
 
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
         amount: u64,
-        min_x: u64,
-        min_y: u64
+        min_a: u64,
+        min_b: u64,
+        expiration: i64
+    ) -> Result<()> {
+        ctx.accounts.withdraw(amount, min_a, min_b, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_single_sided<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
+        amount: u64,
+        receive_a: bool,
+        min_out: u64,
+        expiration: i64
+    ) -> Result<()> {
+        ctx.accounts.withdraw_single_sided(amount, receive_a, min_out, expiration, ctx.remaining_accounts)
+    }
+
+    // Pool administration, gated on `config.authority`
+    pub fn update_fee(ctx: Context<UpdateConfig>, fee: u16) -> Result<()> {
+        ctx.accounts.update_fee(fee)
+    }
+
+    pub fn set_protocol_fee_share(ctx: Context<UpdateConfig>, protocol_fee_share: u16) -> Result<()> {
+        ctx.accounts.set_protocol_fee_share(protocol_fee_share)
+    }
+
+    pub fn collect_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectProtocolFees<'info>>
     ) -> Result<()> {
-        ctx.accounts.withdraw(amount, min_x, min_y)
+        ctx.accounts.collect_protocol_fees(ctx.remaining_accounts)
+    }
+
+    pub fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+        ctx.accounts.pause(flags)
+    }
+
+    pub fn unpause(ctx: Context<UpdateConfig>, flags: u8) -> Result<()> {
+        ctx.accounts.unpause(flags)
+    }
+
+    pub fn set_guardian(ctx: Context<UpdateConfig>, guardian: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.set_guardian(guardian)
+    }
+
+    pub fn propose_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.propose_authority(new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        ctx.accounts.accept_authority()
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.cancel_authority_transfer()
+    }
+
+    pub fn renounce_authority(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.renounce_authority()
+    }
+
+    // Reconcile stored reserves with vault balances; anyone can call it
+    pub fn sync(ctx: Context<SyncReserves>) -> Result<()> {
+        ctx.accounts.sync()
+    }
+
+    pub fn skim<'info>(ctx: Context<'_, '_, 'info, 'info, SkimReserves<'info>>) -> Result<()> {
+        ctx.accounts.skim(ctx.remaining_accounts)
     }
 
     // Bridge functionality for Token-2022 support
@@ -65,7 +187,10 @@ pub mod amm {
         instructions::wrap_for_pool(ctx, amount)
     }
 
-    pub fn unwrap_from_pool(ctx: Context<UnwrapFromPool>, amount: u64) -> Result<()> {
+    pub fn unwrap_from_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnwrapFromPool<'info>>,
+        amount: u64
+    ) -> Result<()> {
         instructions::unwrap_from_pool(ctx, amount)
     }
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {   
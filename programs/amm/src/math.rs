@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, MAX_FEE_BPS, MINIMUM_LIQUIDITY};
+
+// Inverse of the constant product swap: the input (fee included) needed to take
+// exactly `amount_out` from the pool, and the fee part of it. Both divisions round
+// up, in favor of the pool.
+pub fn amount_in_for_exact_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+    fee: u16,
+) -> Result<(u64, u64)> {
+    require!(amount_out != 0, AmmError::InvalidAmount);
+    require!(reserve_in != 0 && reserve_out != 0, AmmError::NoLiquidityInPool);
+    require!(amount_out < reserve_out, AmmError::Insufficientbalance);
+
+    // x * dy / (y - dy)
+    let amount_in_after_fee = (reserve_in as u128)
+        .checked_mul(amount_out as u128)
+        .ok_or(AmmError::Overflow)?
+        .div_ceil((reserve_out - amount_out) as u128);
+
+    // Gross up so that amount_in - fee(amount_in) >= amount_in_after_fee
+    let fee_denominator = MAX_FEE_BPS.checked_sub(fee).ok_or(AmmError::InvalidFee)?;
+    require!(fee_denominator != 0, AmmError::InvalidFee);
+    let amount_in = amount_in_after_fee
+        .checked_mul(MAX_FEE_BPS as u128)
+        .ok_or(AmmError::Overflow)?
+        .div_ceil(fee_denominator as u128);
+
+    let amount_in = u64::try_from(amount_in).map_err(|_| AmmError::Overflow)?;
+    Ok((amount_in, amount_in - amount_in_after_fee as u64))
+}
+
+// Part of a swap fee owed to the protocol, rounded down in favor of LPs
+pub fn protocol_fee(fee_amount: u64, protocol_fee_share: u16) -> Result<u64> {
+    let cut = (fee_amount as u128)
+        .checked_mul(protocol_fee_share as u128)
+        .ok_or(AmmError::Overflow)?
+        / MAX_FEE_BPS as u128;
+    Ok(cut as u64)
+}
+
+// LP supply minted by the first deposit: the geometric mean of both amounts
+pub fn initial_liquidity(amount_x: u64, amount_y: u64) -> Result<u64> {
+    require!(amount_x != 0 && amount_y != 0, AmmError::InvalidAmount);
+    let product = (amount_x as u128) * (amount_y as u128);
+    // sqrt of a u64 * u64 product always fits back into a u64
+    Ok(isqrt(product) as u64)
+}
+
+// LP minted to the first depositor, net of the permanently locked minimum
+pub fn first_deposit_lp(amount_x: u64, amount_y: u64) -> Result<u64> {
+    let liquidity = initial_liquidity(amount_x, amount_y)?;
+    require!(liquidity > MINIMUM_LIQUIDITY, AmmError::LiquidityLessThanMinium);
+    Ok(liquidity - MINIMUM_LIQUIDITY)
+}
+
+// Largest balanced deposit within `desired_x` / `desired_y` as (x, y, lp_out). LP is
+// rounded down and the amounts pulled are rounded up, both in favor of the pool.
+pub fn balanced_deposit(
+    reserve_x: u64,
+    reserve_y: u64,
+    supply: u64,
+    desired_x: u64,
+    desired_y: u64,
+) -> Result<(u64, u64, u64)> {
+    require!(reserve_x != 0 && reserve_y != 0 && supply != 0, AmmError::NoLiquidityInPool);
+    let lp_from_x = (desired_x as u128) * (supply as u128) / (reserve_x as u128);
+    let lp_from_y = (desired_y as u128) * (supply as u128) / (reserve_y as u128);
+    let lp_out = lp_from_x.min(lp_from_y);
+
+    let x = (lp_out * reserve_x as u128).div_ceil(supply as u128);
+    let y = (lp_out * reserve_y as u128).div_ceil(supply as u128);
+    Ok((
+        u64::try_from(x).map_err(|_| AmmError::Overflow)?,
+        u64::try_from(y).map_err(|_| AmmError::Overflow)?,
+        u64::try_from(lp_out).map_err(|_| AmmError::Overflow)?,
+    ))
+}
+
+// Part of a single-sided deposit to swap first so that the remainder matches the
+// post-swap pool ratio. In bps, with g = MAX_FEE_BPS - fee, this is the positive root
+// of g*s^2 + R*(F + g)*s - F*a*R = 0, rounded down.
+pub fn zap_swap_amount(reserve_in: u64, amount_in: u64, fee: u16) -> Result<u64> {
+    require!(reserve_in != 0, AmmError::NoLiquidityInPool);
+    let f = MAX_FEE_BPS as u128;
+    let g = MAX_FEE_BPS.checked_sub(fee).ok_or(AmmError::InvalidFee)? as u128;
+    require!(g != 0, AmmError::InvalidFee);
+    let r = reserve_in as u128;
+    let a = amount_in as u128;
+
+    // s = (sqrt(R * (R*(F + g)^2 + 4*g*F*a)) - R*(F + g)) / (2*g)
+    let linear = r * (f + g);
+    let inner = linear * (f + g) + 4 * g * f * a;
+    let root = isqrt_product(r, inner);
+    let swap_amount = root.saturating_sub(linear) / (2 * g);
+    Ok((swap_amount as u64).min(amount_in))
+}
+
+// Floor of sqrt(x * y) for factors whose product may not fit in a u128. The excess
+// bits are shifted out of `y` in pairs, so the result is exact unless x * y overflows
+// and otherwise low by less than 2^shift.
+fn isqrt_product(x: u128, y: u128) -> u128 {
+    let bits = 256 - x.leading_zeros() - y.leading_zeros();
+    let shift = bits.saturating_sub(128).div_ceil(2);
+    isqrt(x * (y >> (2 * shift))) << shift
+}
+
+// Floor of the square root (Newton's method)
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_in_for_exact_out_rounds_in_favor_of_the_pool() {
+        // ceil(1e6 * 1000 / 999_000) = 1002 reaches the curve, grossed up by 30 bps to 1006
+        assert_eq!(amount_in_for_exact_out(1_000_000, 1_000_000, 1_000, 30).unwrap(), (1_006, 4));
+        assert_eq!(amount_in_for_exact_out(1_000_000, 1_000_000, 1_000, 0).unwrap(), (1_002, 0));
+        assert_eq!(
+            amount_in_for_exact_out(5_000_000_000, 2_000_000_000, 100_000_000, 25).unwrap(),
+            (263_817_439, 659_544)
+        );
+
+        // Swapping the returned input forward pays out at least the requested amount
+        let (amount_in, fee) = amount_in_for_exact_out(1_000_000, 1_000_000, 1_000, 30).unwrap();
+        let net_in = (amount_in - fee) as u128;
+        assert!(1_000_000 * net_in / (1_000_000 + net_in) >= 1_000);
+    }
+
+    #[test]
+    fn amount_in_for_exact_out_rejects_impossible_outputs() {
+        assert!(amount_in_for_exact_out(1_000, 1_000, 0, 30).is_err());
+        assert!(amount_in_for_exact_out(1_000, 1_000, 1_000, 30).is_err());
+        assert!(amount_in_for_exact_out(0, 1_000, 10, 30).is_err());
+        assert!(amount_in_for_exact_out(1_000, 1_000, 10, MAX_FEE_BPS).is_err());
+    }
+
+    #[test]
+    fn protocol_fee_rounds_down_in_favor_of_lps() {
+        assert_eq!(protocol_fee(1_000, 5_000).unwrap(), 500);
+        assert_eq!(protocol_fee(999, 3_333).unwrap(), 332);
+        assert_eq!(protocol_fee(1_000, 0).unwrap(), 0);
+        assert_eq!(protocol_fee(u64::MAX, MAX_FEE_BPS).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn first_deposit_lp_locks_minimum_liquidity() {
+        assert_eq!(first_deposit_lp(4_000_000, 1_000_000).unwrap(), 1_999_000);
+        assert_eq!(first_deposit_lp(u64::MAX, u64::MAX).unwrap(), u64::MAX - MINIMUM_LIQUIDITY);
+        assert!(first_deposit_lp(1_000, 1_000).is_err());
+        assert!(first_deposit_lp(0, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn balanced_deposit_takes_the_binding_side() {
+        // B is in excess: LP follows A and only the matching share of B is pulled
+        assert_eq!(balanced_deposit(1_000, 2_000, 500, 100, 1_000).unwrap(), (100, 200, 50));
+        // LP rounds down, the amounts pulled round up
+        assert_eq!(balanced_deposit(3, 7, 5, 1, 10).unwrap(), (1, 2, 1));
+        assert!(balanced_deposit(0, 2_000, 500, 100, 1_000).is_err());
+        assert!(balanced_deposit(1_000, 2_000, 0, 100, 1_000).is_err());
+    }
+
+    #[test]
+    fn zap_swap_amount_matches_known_optimum() {
+        // Without a fee the root of s^2 + 2Rs - aR = 0 for R = 1e6, a = 3e6 is 1e6
+        assert_eq!(zap_swap_amount(1_000_000, 3_000_000, 0).unwrap(), 1_000_000);
+        assert_eq!(zap_swap_amount(1_000_000_000, 1_000_000, 30).unwrap(), 500_626);
+        assert_eq!(zap_swap_amount(1_000_000_000, 1_000_000_000, 30).unwrap(), 414_835_953);
+    }
+
+    #[test]
+    fn zap_swap_amount_handles_large_reserves() {
+        let exact: u64 = 2_076_020_021_472_193_131;
+        let got = zap_swap_amount(1 << 63, 1 << 62, 30).unwrap();
+        assert!(got <= exact && exact - got < 1_000, "{got}");
+
+        let exact: u64 = 7_679_316_415_652_402_239;
+        let got = zap_swap_amount(u64::MAX, u64::MAX, 100).unwrap();
+        assert!(got <= exact && exact - got < 1_000, "{got}");
+    }
+
+    #[test]
+    fn zap_swap_amount_rejects_empty_pool_and_full_fee() {
+        assert!(zap_swap_amount(0, 1_000, 30).is_err());
+        assert!(zap_swap_amount(1_000, 1_000, MAX_FEE_BPS).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState, mint_close_authority::MintCloseAuthority,
+        non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+        transfer_fee::TransferFeeConfig, transfer_hook::TransferHook, BaseStateWithExtensions,
+        StateWithExtensions,
+    },
+    state::{AccountState, Mint as MintState},
+};
+
+use crate::{
+    error::AmmError, HookRegistry, EXT_DEFAULT_FROZEN, EXT_MINT_CLOSE_AUTHORITY,
+    EXT_PERMANENT_DELEGATE, EXT_RISKY, EXT_TRANSFER_FEE, EXT_TRANSFER_HOOK,
+};
+
+// EXT_* bits for the extensions on `mint`; legacy SPL mints have none. Extensions whose
+// authority or program has been cleared are not counted.
+pub fn mint_extensions(mint: &AccountInfo) -> Result<u16> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    require!(state.get_extension::<NonTransferable>().is_err(), AmmError::NonTransferableMint);
+
+    let mut flags = 0;
+    if state.get_extension::<TransferFeeConfig>().is_ok() {
+        flags |= EXT_TRANSFER_FEE;
+    }
+    if let Ok(hook) = state.get_extension::<TransferHook>() {
+        if Option::<Pubkey>::from(hook.program_id).is_some() {
+            flags |= EXT_TRANSFER_HOOK;
+        }
+    }
+    if let Ok(delegate) = state.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(delegate.delegate).is_some() {
+            flags |= EXT_PERMANENT_DELEGATE;
+        }
+    }
+    if let Ok(close) = state.get_extension::<MintCloseAuthority>() {
+        if Option::<Pubkey>::from(close.close_authority).is_some() {
+            flags |= EXT_MINT_CLOSE_AUTHORITY;
+        }
+    }
+    if let Ok(default_state) = state.get_extension::<DefaultAccountState>() {
+        if default_state.state == AccountState::Frozen as u8 {
+            flags |= EXT_DEFAULT_FROZEN;
+        }
+    }
+    Ok(flags)
+}
+
+// Rejects risky extensions the caller has not opted in to and returns the full set
+pub fn check_mint_extensions(mint: &AccountInfo, allowed: u16) -> Result<u16> {
+    let flags = mint_extensions(mint)?;
+    require!(flags & EXT_RISKY & !allowed == 0, AmmError::MintExtensionNotAllowed);
+    Ok(flags)
+}
+
+pub fn transfer_hook_program(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferHook>()
+        .ok()
+        .and_then(|hook| Option::<Pubkey>::from(hook.program_id)))
+}
+
+// The mint's hook program, if any, must be in the registry. Approvals pinned to a build
+// also need the hook's programdata account, looked up by address in `remaining_accounts`.
+pub fn check_transfer_hook(
+    mint: &AccountInfo,
+    registry: &HookRegistry,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    let Some(program_id) = transfer_hook_program(mint)? else {
+        return Ok(());
+    };
+    let approved = registry.find(&program_id).ok_or(AmmError::HookProgramNotApproved)?;
+    let Some(expected_hash) = approved.program_data_hash else {
+        return Ok(());
+    };
+
+    let loader = <ProgramData as Owner>::owner();
+    let (program_data_key, _) = Pubkey::find_program_address(&[program_id.as_ref()], &loader);
+    let program_data = remaining_accounts
+        .iter()
+        .find(|account| account.key() == program_data_key)
+        .ok_or(AmmError::HookProgramDataMismatch)?;
+    require_keys_eq!(*program_data.owner, loader, AmmError::HookProgramDataMismatch);
+
+    let data = program_data.try_borrow_data()?;
+    let header = UpgradeableLoaderState::size_of_programdata_metadata();
+    require!(data.len() > header, AmmError::HookProgramDataMismatch);
+    require!(hash(&data[header..]).to_bytes() == expected_hash, AmmError::HookProgramDataMismatch);
+    Ok(())
+}
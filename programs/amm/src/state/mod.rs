@@ -1,21 +1,112 @@
 use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, math::protocol_fee};
+
+// Fees are expressed in basis points; 10_000 would take the whole input
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+// LP minted on the first deposit to a dead PDA and never redeemable; this keeps
+// the first depositor from inflating the share price against later LPs
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+// Independent pause bits stored in `config.paused`
+pub const PAUSE_SWAP: u8 = 1 << 0;
+pub const PAUSE_DEPOSIT: u8 = 1 << 1;
+pub const PAUSE_WITHDRAW: u8 = 1 << 2;
+pub const PAUSE_BRIDGE_WRAP: u8 = 1 << 3;
+pub const PAUSE_BRIDGE_UNWRAP: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_SWAP | PAUSE_DEPOSIT | PAUSE_WITHDRAW | PAUSE_BRIDGE_WRAP | PAUSE_BRIDGE_UNWRAP;
+
+// Mint extension bits recorded in `config.extensions_a` / `config.extensions_b`
+pub const EXT_TRANSFER_FEE: u16 = 1 << 0;
+pub const EXT_TRANSFER_HOOK: u16 = 1 << 1;
+pub const EXT_PERMANENT_DELEGATE: u16 = 1 << 2;
+pub const EXT_MINT_CLOSE_AUTHORITY: u16 = 1 << 3;
+pub const EXT_DEFAULT_FROZEN: u16 = 1 << 4;
+// Extensions that let a third party move, freeze or destroy pool funds; the pool
+// creator must opt in to each one through `allowed_extensions`. Hook programs are
+// not opt-in: they must be approved in the `HookRegistry`.
+pub const EXT_RISKY: u16 = EXT_PERMANENT_DELEGATE | EXT_MINT_CLOSE_AUTHORITY | EXT_DEFAULT_FROZEN;
+
+pub const MAX_APPROVED_HOOKS: usize = 16;
+
 #[account]
 #[derive(InitSpace)]
 pub struct config{
     pub seed:u64,
     pub authority:Option<Pubkey>,
-    pub mint:Pubkey,
+    pub pending_authority:Option<Pubkey>, // Proposed authority, must sign to accept
+    pub mint_a:Pubkey, // X side of the curve
+    pub mint_b:Pubkey, // Y side of the curve
+    pub token_program_a:Pubkey, // Owning program of mint_a (Token or Token-2022)
+    pub token_program_b:Pubkey, // Owning program of mint_b
+    pub extensions_a:u16, // EXT_* bits present on mint_a when the pool was created
+    pub extensions_b:u16, // EXT_* bits present on mint_b
     pub fee:u16,
-    pub  locked:bool,
+    pub protocol_fee_share:u16, // Basis points of every swap fee accrued to the protocol
+    pub protocol_fees_a:u64, // mint_a tokens in vault_a owed to the protocol
+    pub protocol_fees_b:u64, // mint_b tokens in vault_b owed to the protocol
+    pub reserve_a:u64, // A side of the curve; donations to the vault are not counted
+    pub reserve_b:u64, // B side of the curve
+    pub paused:u8, // Bitmask of PAUSE_* flags
+    pub guardian:Option<Pubkey>, // Can only pause, never unpause or change fees
     pub config_bump:u8,
-    pub wsol_mint: Pubkey,
-    pub sol_vault_bump: u8, 
     pub lp_bump:u8,
     pub bridge_config: Option<Pubkey>, // Reference to bridge config if this pool uses bridge tokens
     pub is_bridge_pool: bool, 
     // Whether this pool trades bridge tokens
 }
 
+impl config {
+    pub fn assert_authority(&self, signer: &Pubkey) -> Result<()> {
+        let authority = self.authority.ok_or(AmmError::NoAuthoritySet)?;
+        require_keys_eq!(authority, *signer, AmmError::InvalidAuthority);
+        Ok(())
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+
+    // Fees are charged on the input side, so the protocol cut accrues there too
+    pub fn accrue_protocol_fee(&mut self, is_a_in: bool, fee_amount: u64) -> Result<u64> {
+        let cut = protocol_fee(fee_amount, self.protocol_fee_share)?;
+        let accrued = if is_a_in {
+            &mut self.protocol_fees_a
+        } else {
+            &mut self.protocol_fees_b
+        };
+        *accrued = accrued.checked_add(cut).ok_or(AmmError::Overflow)?;
+        Ok(cut)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ApprovedHook {
+    pub program_id: Pubkey,
+    // sha256 of the hook's programdata after the loader header; pins the approval to
+    // one build so an upgrade has to be re-approved
+    pub program_data_hash: Option<[u8; 32]>,
+}
+
+// Global list of transfer hook programs that pool and bridge mints may point at
+#[account]
+#[derive(InitSpace)]
+pub struct HookRegistry {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>, // Proposed admin, must sign to accept
+    #[max_len(MAX_APPROVED_HOOKS)]
+    pub hooks: Vec<ApprovedHook>,
+    pub bump: u8,
+}
+
+impl HookRegistry {
+    pub fn find(&self, program_id: &Pubkey) -> Option<&ApprovedHook> {
+        self.hooks.iter().find(|hook| hook.program_id == *program_id)
+    }
+}
+
 // Bridge-related state structures for AMM integration
 #[account]
 #[derive(InitSpace)]
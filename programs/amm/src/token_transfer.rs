@@ -0,0 +1,136 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        onchain::invoke_transfer_checked,
+        state::Mint as MintState,
+    },
+    token_interface::{close_account, sync_native, CloseAccount, SyncNative},
+};
+
+use crate::error::AmmError;
+
+// `transfer_checked` against the mint's owning program. Anchor's CPI helper drops the
+// transfer hook accounts, so the ExtraAccountMetaList-resolved accounts (plus the hook
+// program and validation account) are forwarded from `remaining_accounts` instead.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from.clone(),
+        mint.clone(),
+        to.clone(),
+        authority.clone(),
+        remaining_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// Transfer fee in force this epoch; `None` for legacy mints and mints without TransferFeeConfig
+fn epoch_transfer_fee(mint: &AccountInfo) -> Result<Option<TransferFee>> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Some(*config.get_epoch_fee(Clock::get()?.epoch)),
+        Err(_) => None,
+    })
+}
+
+// What the destination receives when `amount` is sent
+pub fn amount_after_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match epoch_transfer_fee(mint)? {
+        Some(fee) => Ok(fee.calculate_post_fee_amount(amount).ok_or(AmmError::Overflow)?),
+        None => Ok(amount),
+    }
+}
+
+// Smallest amount to send so that the destination receives `post_fee_amount`
+pub fn amount_before_transfer_fee(mint: &AccountInfo, post_fee_amount: u64) -> Result<u64> {
+    match epoch_transfer_fee(mint)? {
+        Some(fee) => Ok(fee.calculate_pre_fee_amount(post_fee_amount).ok_or(AmmError::Overflow)?),
+        None => Ok(post_fee_amount),
+    }
+}
+
+// Wrapped SOL under either token program
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+// Funds a wrapped SOL side's user account from the owner's lamports; other mints pass through
+pub fn wrap_if_native<'info>(
+    mint: &Pubkey,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if !is_native_mint(mint) {
+        return Ok(());
+    }
+    wrap_native(system_program, token_program, owner, account, amount)
+}
+
+// A wrapped SOL side's user account is temporary: closing it leaves the owner with native SOL.
+// Deposit, Swap and Withdraw only accept such an account while it is empty, so closing it
+// returns nothing but what the instruction itself wrapped or paid out.
+pub fn unwrap_if_native<'info>(
+    mint: &Pubkey,
+    token_program: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+) -> Result<()> {
+    if !is_native_mint(mint) {
+        return Ok(());
+    }
+    unwrap_native(token_program, owner, account)
+}
+
+// Moves `amount` lamports from the owner into their WSOL account and syncs its token balance
+fn wrap_native<'info>(
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer { from: owner.clone(), to: account.clone() },
+        ),
+        amount,
+    )?;
+    sync_native(CpiContext::new(token_program.clone(), SyncNative { account: account.clone() }))
+}
+
+// Closes the owner's WSOL account, returning its whole balance to them as lamports
+fn unwrap_native<'info>(
+    token_program: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+) -> Result<()> {
+    close_account(CpiContext::new(
+        token_program.clone(),
+        CloseAccount { account: account.clone(), destination: owner.clone(), authority: owner.clone() },
+    ))
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::HookError,
+    events::{WalletApproved, WalletRevoked},
+    HookConfig, WalletRecord,
+};
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct AddWallet<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    // Re-adding an approved wallet just refreshes its expiry
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + WalletRecord::INIT_SPACE,
+        seeds = [b"wallet-record", hook_config.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub wallet_record: Account<'info, WalletRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddWallet<'info> {
+    pub fn add_wallet(&mut self, owner: Pubkey, expires_at: i64, bump: u8) -> Result<()> {
+        require!(
+            expires_at == 0 || expires_at > Clock::get()?.unix_timestamp,
+            HookError::InvalidExpiry
+        );
+        let mint = self.hook_config.mint;
+        self.wallet_record.set_inner(WalletRecord {
+            mint,
+            owner,
+            expires_at,
+            bump,
+        });
+        emit!(WalletApproved { mint, owner, expires_at });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct RevokeWallet<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"wallet-record", hook_config.mint.as_ref(), owner.as_ref()],
+        bump = wallet_record.bump
+    )]
+    pub wallet_record: Account<'info, WalletRecord>,
+}
+
+impl<'info> RevokeWallet<'info> {
+    pub fn revoke_wallet(&mut self, owner: Pubkey) -> Result<()> {
+        emit!(WalletRevoked { mint: self.hook_config.mint, owner });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct SetWalletExpiry<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"wallet-record", hook_config.mint.as_ref(), owner.as_ref()],
+        bump = wallet_record.bump
+    )]
+    pub wallet_record: Account<'info, WalletRecord>,
+}
+
+impl<'info> SetWalletExpiry<'info> {
+    // A past timestamp expires the wallet immediately but keeps the record for auditing
+    pub fn set_wallet_expiry(&mut self, owner: Pubkey, expires_at: i64) -> Result<()> {
+        self.wallet_record.expires_at = expires_at;
+        emit!(WalletApproved { mint: self.hook_config.mint, owner, expires_at });
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer},
+};
+
+use crate::{
+    error::HookError,
+    events::{WalletBlocked, WalletUnblocked},
+    BlockedWallet, HookConfig,
+};
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct BlockWallet<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + BlockedWallet::INIT_SPACE,
+        seeds = [BlockedWallet::SEED, hook_config.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub blocked_wallet: Account<'info, BlockedWallet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BlockWallet<'info> {
+    pub fn block_wallet(&mut self, owner: Pubkey, bump: u8) -> Result<()> {
+        let mint = self.hook_config.mint;
+        self.blocked_wallet.set_inner(BlockedWallet {
+            mint,
+            owner,
+            blocked_at: Clock::get()?.unix_timestamp,
+            bump,
+        });
+        emit!(WalletBlocked { mint, owner });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct UnblockWallet<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [BlockedWallet::SEED, hook_config.mint.as_ref(), owner.as_ref()],
+        bump = blocked_wallet.bump
+    )]
+    pub blocked_wallet: Account<'info, BlockedWallet>,
+}
+
+impl<'info> UnblockWallet<'info> {
+    pub fn unblock_wallet(&mut self, owner: Pubkey) -> Result<()> {
+        emit!(WalletUnblocked { mint: self.hook_config.mint, owner });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct BlockWallets<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BlockWallets<'info> {
+    // Owners that are already blocked are skipped, so a list can be re-submitted safely
+    pub fn block_wallets(&self, owners: &[Pubkey], records: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(owners.len() == records.len(), HookError::BlocklistAccountMismatch);
+
+        let mint = self.hook_config.mint;
+        let blocked_at = Clock::get()?.unix_timestamp;
+        let space = 8 + BlockedWallet::INIT_SPACE;
+        let lamports = Rent::get()?.minimum_balance(space);
+
+        for (owner, record) in owners.iter().zip(records) {
+            let (expected, bump) = Pubkey::find_program_address(
+                &[BlockedWallet::SEED, mint.as_ref(), owner.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(record.key(), expected, HookError::BlocklistAccountMismatch);
+            if record.owner == &crate::ID {
+                continue;
+            }
+
+            let signer_seeds: &[&[&[u8]]] = &[&[BlockedWallet::SEED, mint.as_ref(), owner.as_ref(), &[bump]]];
+            let system_program = self.system_program.to_account_info();
+            let current_lamports = record.lamports();
+            if current_lamports == 0 {
+                create_account(
+                    CpiContext::new(
+                        system_program,
+                        CreateAccount {
+                            from: self.admin.to_account_info(),
+                            to: record.clone(),
+                        },
+                    )
+                    .with_signer(signer_seeds),
+                    lamports,
+                    space as u64,
+                    &crate::ID,
+                )?;
+            } else {
+                // Anyone can send lamports to the PDA ahead of time, which makes create_account
+                // fail; top up rent and allocate/assign instead, as Anchor's init does
+                let shortfall = lamports.saturating_sub(current_lamports);
+                if shortfall > 0 {
+                    transfer(
+                        CpiContext::new(
+                            system_program.clone(),
+                            Transfer {
+                                from: self.admin.to_account_info(),
+                                to: record.clone(),
+                            },
+                        ),
+                        shortfall,
+                    )?;
+                }
+                allocate(
+                    CpiContext::new(system_program.clone(), Allocate { account_to_allocate: record.clone() })
+                        .with_signer(signer_seeds),
+                    space as u64,
+                )?;
+                assign(
+                    CpiContext::new(system_program, Assign { account_to_assign: record.clone() })
+                        .with_signer(signer_seeds),
+                    &crate::ID,
+                )?;
+            }
+            BlockedWallet {
+                mint,
+                owner: *owner,
+                blocked_at,
+                bump,
+            }
+            .try_serialize(&mut &mut record.try_borrow_mut_data()?[..])?;
+            emit!(WalletBlocked { mint, owner: *owner });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum HookError {
+    #[msg("Fee is greater than 100%")]
+    InvalidFee,
+    #[msg("Minimum fee exceeds the maximum fee")]
+    InvalidFeeBounds,
+    #[msg("Signer is not the mint authority")]
+    NotMintAuthority,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("No admin transfer pending")]
+    NoPendingAdmin,
+    #[msg("Signer is not the pending admin")]
+    InvalidPendingAdmin,
+    #[msg("OverFlow detected")]
+    Overflow,
+    #[msg("Invalid Amount")]
+    InvalidAmount,
+    #[msg("Not enough collected fees")]
+    InsufficientFees,
+    #[msg("Wallet is not on the mint's allowlist")]
+    WalletNotApproved,
+    #[msg("Wallet's allowlist approval has expired")]
+    WalletApprovalExpired,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Wallet is blocked for this mint")]
+    WalletBlocked,
+    #[msg("Blocklist accounts do not match the owners")]
+    BlocklistAccountMismatch,
+    #[msg("Transfer exceeds the per-transfer limit")]
+    TransferAmountExceedsLimit,
+    #[msg("Transfer exceeds the owner's 24h limit")]
+    DailyLimitExceeded,
+    #[msg("Owner has no TransferStats account for this mint")]
+    TransferStatsMissing,
+    #[msg("Destination balance would exceed the maximum wallet balance")]
+    MaxWalletBalanceExceeded,
+    #[msg("Token account is already exempt")]
+    ExemptionAlreadyExists,
+    #[msg("Token account is not exempt")]
+    ExemptionNotFound,
+    #[msg("Too many exemptions")]
+    TooManyExemptions,
+    #[msg("Vesting schedule must satisfy start <= cliff <= end and start < end")]
+    InvalidVestingSchedule,
+    #[msg("Transfer would move tokens that are still locked")]
+    TokensLocked,
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::HookConfig;
+
+// Full settings after any admin change, so indexers never merge partial updates
+#[event]
+pub struct HookConfigUpdated {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub min_fee: u64,
+    pub max_fee: u64,
+    pub allowlist_enabled: bool,
+    pub max_transfer_amount: u64,
+    pub daily_transfer_limit: u64,
+    pub max_wallet_balance: u64,
+    pub balance_exemptions: Vec<Pubkey>,
+    pub velocity_exemptions: Vec<Pubkey>,
+}
+
+impl From<&HookConfig> for HookConfigUpdated {
+    fn from(config: &HookConfig) -> Self {
+        Self {
+            mint: config.mint,
+            admin: config.admin,
+            fee_bps: config.fee_bps,
+            min_fee: config.min_fee,
+            max_fee: config.max_fee,
+            allowlist_enabled: config.allowlist_enabled,
+            max_transfer_amount: config.max_transfer_amount,
+            daily_transfer_limit: config.daily_transfer_limit,
+            max_wallet_balance: config.max_wallet_balance,
+            balance_exemptions: config.balance_exemptions.clone(),
+            velocity_exemptions: config.velocity_exemptions.clone(),
+        }
+    }
+}
+
+#[event]
+pub struct HookAdminProposed {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct HookAdminAccepted {
+    pub mint: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WalletApproved {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct WalletRevoked {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct WalletBlocked {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct WalletUnblocked {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct VestingScheduleCreated {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub total_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+}
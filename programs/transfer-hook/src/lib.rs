@@ -1,6 +1,7 @@
 use anchor_lang::{
     prelude::*,
-    system_program::{create_account, CreateAccount},
+    solana_program::program_option::COption,
+    system_program::{create_account, transfer, CreateAccount, Transfer},
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -12,6 +13,20 @@ use spl_tlv_account_resolution::{
 use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
 
 declare_id!("88CNX3Y7TyzjPtD76YhpmnPAsrmhSsYRVS5ad2wKMjuk");
+pub mod error;
+pub use error::*;
+pub mod events;
+pub use events::*;
+pub mod allowlist;
+pub use allowlist::*;
+pub mod blocklist;
+pub use blocklist::*;
+pub mod velocity;
+pub use velocity::*;
+pub mod vesting;
+pub use vesting::*;
+pub mod state;
+pub use state::*;
 
 #[program]
 pub mod transfer_hook {
@@ -20,38 +35,11 @@ pub mod transfer_hook {
     pub fn initialize_extra_account_meta_list(
         ctx: Context<InitializeExtraAccountMetaList>,
     ) -> Result<()> {
-        let account_metas = vec![
-            ExtraAccountMeta::new_with_pubkey(&ctx.accounts.wsol_mint.key(), false, false)?,
-            ExtraAccountMeta::new_with_pubkey(&ctx.accounts.token_program.key(), false, false)?,
-            ExtraAccountMeta::new_with_pubkey(&ctx.accounts.associated_token_program.key(), false, false)?,
-            ExtraAccountMeta::new_with_seeds(
-                &[Seed::Literal {
-                    bytes: b"delegate".to_vec(),
-                }],
-                false, // is_signer (signature provided via with_signer)
-                true,  // is_writable
-            )?,
-            ExtraAccountMeta::new_external_pda_with_seeds(
-                7, // associated token program index
-                &[
-                    Seed::AccountKey { index: 8 }, // owner (delegate PDA)
-                    Seed::AccountKey { index: 6 }, // token program (Token-2022)
-                    Seed::AccountKey { index: 5 }, // WSOL mint
-                ],
-                false, // is_signer
-                true,  // is_writable
-            )?,
-            ExtraAccountMeta::new_external_pda_with_seeds(
-                7, // associated token program index
-                &[
-                    Seed::AccountKey { index: 3 }, // owner (source owner)
-                    Seed::AccountKey { index: 6 }, // token program (Token-2022)
-                    Seed::AccountKey { index: 5 }, // WSOL mint
-                ],
-                false, // is_signer
-                true,  // is_writable
-            )?,
-        ];
+        let account_metas = extra_account_metas(
+            &ctx.accounts.wsol_mint.key(),
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.associated_token_program.key(),
+        )?;
 
         let account_size = ExtraAccountMetaList::size_of(account_metas.len())? as u64;
         let lamports = Rent::get()?.minimum_balance(account_size as usize);
@@ -85,16 +73,312 @@ pub mod transfer_hook {
             &account_metas,
         )?;
 
+        ctx.accounts.hook_config.set_inner(HookConfig::new(
+            ctx.accounts.mint.key(),
+            ctx.accounts.payer.key(),
+            ctx.bumps.hook_config,
+        ));
+
+        Ok(())
+    }
+
+    // For mints whose hook was set up before HookConfig existed
+    pub fn init_hook_config(ctx: Context<InitHookConfig>) -> Result<()> {
+        ctx.accounts.hook_config.set_inner(HookConfig::new(
+            ctx.accounts.mint.key(),
+            ctx.accounts.payer.key(),
+            ctx.bumps.hook_config,
+        ));
+        Ok(())
+    }
+
+    // Rewrites an existing list with the current metas, growing the account when needed.
+    // Mints set up by an older hook build must run this before new checks can resolve.
+    pub fn update_extra_account_meta_list(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+        let account_metas = extra_account_metas(
+            &ctx.accounts.wsol_mint.key(),
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.associated_token_program.key(),
+        )?;
+        let account_size = ExtraAccountMetaList::size_of(account_metas.len())?;
+        let meta_list = ctx.accounts.extra_account_meta_list.to_account_info();
+        let current_size = meta_list.data_len();
+
+        if account_size > current_size {
+            let shortfall = Rent::get()?
+                .minimum_balance(account_size)
+                .saturating_sub(meta_list.lamports());
+            if shortfall > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.admin.to_account_info(),
+                            to: meta_list.clone(),
+                        },
+                    ),
+                    shortfall,
+                )?;
+            }
+            meta_list.resize(account_size)?;
+        }
+
+        ExtraAccountMetaList::update::<ExecuteInstruction>(
+            &mut meta_list.try_borrow_mut_data()?,
+            &account_metas,
+        )?;
+
+        // Shrink only after the update so the old TLV entry still fits while it is rewritten
+        if account_size < current_size {
+            meta_list.resize(account_size)?;
+        }
+        Ok(())
+    }
+
+    pub fn update_hook_config(
+        ctx: Context<UpdateHookConfig>,
+        fee_bps: u16,
+        min_fee: u64,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, HookError::InvalidFee);
+        require!(min_fee <= max_fee, HookError::InvalidFeeBounds);
+
+        let hook_config = &mut ctx.accounts.hook_config;
+        hook_config.fee_bps = fee_bps;
+        hook_config.min_fee = min_fee;
+        hook_config.max_fee = max_fee;
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    // Admin rotation is two-step: the proposed key has to sign `accept_hook_admin`.
+    // A new proposal replaces any pending one.
+    pub fn propose_hook_admin(ctx: Context<UpdateHookConfig>, new_admin: Pubkey) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        hook_config.pending_admin = Some(new_admin);
+        emit!(HookAdminProposed {
+            mint: hook_config.mint,
+            admin: hook_config.admin,
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+
+    pub fn accept_hook_admin(ctx: Context<AcceptHookAdmin>) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        let pending = hook_config.pending_admin.ok_or(HookError::NoPendingAdmin)?;
+        require_keys_eq!(pending, ctx.accounts.pending_admin.key(), HookError::InvalidPendingAdmin);
+        let previous_admin = hook_config.admin;
+        hook_config.admin = pending;
+        hook_config.pending_admin = None;
+        emit!(HookAdminAccepted {
+            mint: hook_config.mint,
+            previous_admin,
+            new_admin: pending,
+        });
+        Ok(())
+    }
+
+    // The delegate PDA only signs inside the hook otherwise, so this is the one way out
+    // for collected fees
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        require!(amount != 0, HookError::InvalidAmount);
+        require!(
+            ctx.accounts.delegate_wsol_token_account.amount >= amount,
+            HookError::InsufficientFees
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"delegate", mint_key.as_ref(), &[ctx.bumps.delegate]]];
+
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx
+                        .accounts
+                        .delegate_wsol_token_account
+                        .to_account_info(),
+                    mint: ctx.accounts.wsol_mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.delegate.to_account_info(),
+                },
+            )
+            .with_signer(signer_seeds),
+            amount,
+            ctx.accounts.wsol_mint.decimals,
+        )?;
+
+        emit!(FeesWithdrawn {
+            mint: mint_key,
+            admin: ctx.accounts.admin.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    pub fn set_allowlist_mode(ctx: Context<UpdateHookConfig>, enabled: bool) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        hook_config.allowlist_enabled = enabled;
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    // Zero disables either limit
+    pub fn set_velocity_limits(
+        ctx: Context<UpdateHookConfig>,
+        max_transfer_amount: u64,
+        daily_transfer_limit: u64,
+    ) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        hook_config.max_transfer_amount = max_transfer_amount;
+        hook_config.daily_transfer_limit = daily_transfer_limit;
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    // Meant for the pool and bridge vaults: every swap out of a vault is sent by the one
+    // pool owner, so a per-owner limit would cap the whole pool's trading volume
+    pub fn add_velocity_exemption(ctx: Context<UpdateHookConfig>, token_account: Pubkey) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        let exemptions = &mut hook_config.velocity_exemptions;
+        require!(!exemptions.contains(&token_account), HookError::ExemptionAlreadyExists);
+        require!(exemptions.len() < MAX_VELOCITY_EXEMPTIONS, HookError::TooManyExemptions);
+        exemptions.push(token_account);
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    pub fn remove_velocity_exemption(ctx: Context<UpdateHookConfig>, token_account: Pubkey) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        let exemptions = &mut hook_config.velocity_exemptions;
+        let len = exemptions.len();
+        exemptions.retain(|exempt| *exempt != token_account);
+        require!(exemptions.len() < len, HookError::ExemptionNotFound);
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    // Zero disables the cap
+    pub fn set_max_wallet_balance(ctx: Context<UpdateHookConfig>, max_wallet_balance: u64) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        hook_config.max_wallet_balance = max_wallet_balance;
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    // Meant for the AMM pool vault (`vault_a`/`vault_b` ATA of the pool config) and the
+    // bridge `pool_vault_token_account`, which hold pooled rather than owned balances
+    pub fn add_balance_exemption(ctx: Context<UpdateHookConfig>, token_account: Pubkey) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        let exemptions = &mut hook_config.balance_exemptions;
+        require!(!exemptions.contains(&token_account), HookError::ExemptionAlreadyExists);
+        require!(exemptions.len() < MAX_BALANCE_EXEMPTIONS, HookError::TooManyExemptions);
+        exemptions.push(token_account);
+        emit!(HookConfigUpdated::from(&**hook_config));
+        Ok(())
+    }
+
+    pub fn remove_balance_exemption(ctx: Context<UpdateHookConfig>, token_account: Pubkey) -> Result<()> {
+        let hook_config = &mut ctx.accounts.hook_config;
+        let exemptions = &mut hook_config.balance_exemptions;
+        let len = exemptions.len();
+        exemptions.retain(|exempt| *exempt != token_account);
+        require!(exemptions.len() < len, HookError::ExemptionNotFound);
+        emit!(HookConfigUpdated::from(&**hook_config));
         Ok(())
     }
 
+    pub fn create_vesting_schedule(
+        ctx: Context<CreateVestingSchedule>,
+        owner: Pubkey,
+        total_amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_vesting_schedule(owner, total_amount, start_ts, cliff_ts, end_ts, ctx.bumps.vesting_schedule)
+    }
+
+    pub fn init_transfer_stats(ctx: Context<InitTransferStats>, owner: Pubkey) -> Result<()> {
+        ctx.accounts.init_transfer_stats(owner, ctx.bumps.transfer_stats)
+    }
+
+    pub fn add_wallet(ctx: Context<AddWallet>, owner: Pubkey, expires_at: i64) -> Result<()> {
+        ctx.accounts.add_wallet(owner, expires_at, ctx.bumps.wallet_record)
+    }
+
+    pub fn revoke_wallet(ctx: Context<RevokeWallet>, owner: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_wallet(owner)
+    }
+
+    pub fn set_wallet_expiry(ctx: Context<SetWalletExpiry>, owner: Pubkey, expires_at: i64) -> Result<()> {
+        ctx.accounts.set_wallet_expiry(owner, expires_at)
+    }
+
+    pub fn block_wallet(ctx: Context<BlockWallet>, owner: Pubkey) -> Result<()> {
+        ctx.accounts.block_wallet(owner, ctx.bumps.blocked_wallet)
+    }
+
+    pub fn unblock_wallet(ctx: Context<UnblockWallet>, owner: Pubkey) -> Result<()> {
+        ctx.accounts.unblock_wallet(owner)
+    }
+
+    // `remaining_accounts` holds the BlockedWallet PDA of each owner, in order
+    pub fn block_wallets<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BlockWallets<'info>>,
+        owners: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.block_wallets(&owners, ctx.remaining_accounts)
+    }
+
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
-        let fee_amount = amount / 1000;
+        BlockedWallet::assert_not_blocked(&ctx.accounts.source_blocked_wallet)?;
+        BlockedWallet::assert_not_blocked(&ctx.accounts.destination_blocked_wallet)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let hook_config = &ctx.accounts.hook_config;
+        if hook_config.allowlist_enabled {
+            WalletRecord::assert_approved(&ctx.accounts.source_wallet_record, now)?;
+            WalletRecord::assert_approved(&ctx.accounts.destination_wallet_record, now)?;
+        }
+
+        if hook_config.velocity_limited(&ctx.accounts.source_token.key()) {
+            require!(
+                hook_config.max_transfer_amount == 0 || amount <= hook_config.max_transfer_amount,
+                HookError::TransferAmountExceedsLimit
+            );
+            if hook_config.daily_transfer_limit != 0 {
+                TransferStats::record_transfer(
+                    &ctx.accounts.source_transfer_stats,
+                    now,
+                    amount,
+                    hook_config.daily_transfer_limit,
+                )?;
+            }
+        }
+        // The hook runs after the transfer, so these are the new balances
+        VestingSchedule::assert_unlocked(
+            &ctx.accounts.source_vesting_schedule,
+            now,
+            ctx.accounts.source_token.amount,
+        )?;
+        let destination = &ctx.accounts.destination_token;
+        require!(
+            hook_config.balance_allowed(&destination.key(), destination.amount),
+            HookError::MaxWalletBalanceExceeded
+        );
+
+        let fee_amount = ctx.accounts.hook_config.fee_for(amount)?;
         if fee_amount == 0 {
             return Ok(());
         }
 
-        let signer_seeds: &[&[&[u8]]] = &[&[b"delegate", &[ctx.bumps.delegate]]];
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"delegate", mint_key.as_ref(), &[ctx.bumps.delegate]]];
 
         transfer_checked(
             CpiContext::new(
@@ -136,6 +420,139 @@ pub mod transfer_hook {
     }
 }
 
+// Accounts the hook needs on every Execute, shared by setup and later migrations
+fn extra_account_metas(
+    wsol_mint: &Pubkey,
+    token_program: &Pubkey,
+    associated_token_program: &Pubkey,
+) -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey(wsol_mint, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(token_program, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(associated_token_program, false, false)?,
+        // Fee delegate is per mint, so each issuer's fees land in their own WSOL account
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"delegate".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer (signature provided via with_signer)
+            true,  // is_writable
+        )?,
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            7, // associated token program index
+            &[
+                Seed::AccountKey { index: 8 }, // owner (delegate PDA)
+                Seed::AccountKey { index: 6 }, // token program (Token-2022)
+                Seed::AccountKey { index: 5 }, // WSOL mint
+            ],
+            false, // is_signer
+            true,  // is_writable
+        )?,
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            7, // associated token program index
+            &[
+                Seed::AccountKey { index: 3 }, // owner (source owner)
+                Seed::AccountKey { index: 6 }, // token program (Token-2022)
+                Seed::AccountKey { index: 5 }, // WSOL mint
+            ],
+            false, // is_signer
+            true,  // is_writable
+        )?,
+        // Per-mint fee settings
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"hook-config".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        // Allowlist records; resolved even when absent so the mint can switch modes
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"wallet-record".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountKey { index: 3 }, // source owner
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"wallet-record".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: 2, // destination token account
+                    data_index: 32,   // owner field
+                    length: 32,
+                },
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        // Blocklist entries; the transfer fails if either exists
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"blocked-wallet".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountKey { index: 3 }, // source owner
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"blocked-wallet".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData {
+                    account_index: 2, // destination token account
+                    data_index: 32,   // owner field
+                    length: 32,
+                },
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        // Source owner's rolling transfer volume, updated on every transfer
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"transfer-stats".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountKey { index: 3 }, // source owner
+            ],
+            false, // is_signer
+            true,  // is_writable
+        )?,
+        // Source owner's lockup, if any
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"vesting".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountKey { index: 3 }, // source owner
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+    ])
+}
+
 #[derive(Accounts)]
 pub struct InitializeExtraAccountMetaList<'info> {
     #[account(mut)]
@@ -149,13 +566,126 @@ pub struct InitializeExtraAccountMetaList<'info> {
     )]
     pub extra_account_meta_list: AccountInfo<'info>,
 
+    // Only the mint authority can set up the hook, and becomes the HookConfig admin
+    #[account(constraint = mint.mint_authority == COption::Some(payer.key()) @ HookError::NotMintAuthority)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HookConfig::INIT_SPACE,
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitHookConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Same gate as initialize_extra_account_meta_list; the mint authority becomes the admin
+    #[account(constraint = mint.mint_authority == COption::Some(payer.key()) @ HookError::NotMintAuthority)]
     pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HookConfig::INIT_SPACE,
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: ExtraAccountMetaList account; derived and owned by this program
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", hook_config.mint.as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
     pub wsol_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateHookConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptHookAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", mint.key().as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized,
+        has_one = mint
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Fee authority PDA; only signs here
+    #[account(seeds = [b"delegate", mint.key().as_ref()], bump)]
+    pub delegate: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = wsol_mint, token::authority = delegate, token::token_program = token_program)]
+    pub delegate_wsol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = wsol_mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
 // Order-sensitive; first 4 are the canonical Token-2022 accounts
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
@@ -178,8 +708,8 @@ pub struct TransferHook<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 
-    // Delegate PDA used as the fee authority
-    #[account(mut, seeds = [b"delegate"], bump)]
+    // Per-mint delegate PDA used as the fee authority
+    #[account(mut, seeds = [b"delegate", mint.key().as_ref()], bump)]
     pub delegate: SystemAccount<'info>,
 
     #[account(mut, token::mint = wsol_mint, token::authority = delegate)]
@@ -187,4 +717,31 @@ pub struct TransferHook<'info> {
 
     #[account(mut, token::mint = wsol_mint, token::authority = owner)]
     pub sender_wsol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"hook-config", mint.key().as_ref()], bump = hook_config.bump)]
+    pub hook_config: Account<'info, HookConfig>,
+
+    /// CHECK: Source owner's WalletRecord; may not exist, checked in the handler in allowlist mode
+    #[account(seeds = [b"wallet-record", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub source_wallet_record: UncheckedAccount<'info>,
+
+    /// CHECK: Destination owner's WalletRecord; same as above
+    #[account(seeds = [b"wallet-record", mint.key().as_ref(), destination_token.owner.as_ref()], bump)]
+    pub destination_wallet_record: UncheckedAccount<'info>,
+
+    /// CHECK: Source owner's BlockedWallet; normally does not exist
+    #[account(seeds = [b"blocked-wallet", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub source_blocked_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Destination owner's BlockedWallet; normally does not exist
+    #[account(seeds = [b"blocked-wallet", mint.key().as_ref(), destination_token.owner.as_ref()], bump)]
+    pub destination_blocked_wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Source owner's TransferStats; must exist once the mint has a daily limit
+    #[account(mut, seeds = [b"transfer-stats", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub source_transfer_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Source owner's VestingSchedule; absent for owners without a lockup
+    #[account(seeds = [b"vesting", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub source_vesting_schedule: UncheckedAccount<'info>,
 }
\ No newline at end of file
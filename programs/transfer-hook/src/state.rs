@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HookError;
+
+pub const MAX_FEE_BPS: u16 = 10_000;
+pub const DEFAULT_FEE_BPS: u16 = 10;
+
+// The daily limit is a trailing 24h window kept as one bucket per hour
+pub const VELOCITY_BUCKETS: usize = 24;
+pub const VELOCITY_BUCKET_SECONDS: i64 = 3_600;
+
+pub const MAX_BALANCE_EXEMPTIONS: usize = 8;
+pub const MAX_VELOCITY_EXEMPTIONS: usize = 8;
+
+// Fee settings for one hooked mint, seeded by [b"hook-config", mint]
+#[account]
+#[derive(InitSpace)]
+pub struct HookConfig {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>, // Proposed admin, must sign to accept
+    pub fee_bps: u16, // WSOL fee per transfer, in basis points of the transferred amount
+    pub min_fee: u64, // Floor applied to every non-zero transfer
+    pub max_fee: u64, // Cap on the fee of a single transfer
+    pub allowlist_enabled: bool, // Both owners of every transfer need an active WalletRecord
+    pub max_transfer_amount: u64, // Largest single transfer; 0 is unlimited
+    pub daily_transfer_limit: u64, // Most an owner can send in any 24h; 0 is unlimited
+    pub max_wallet_balance: u64, // Largest balance a destination may end up with; 0 is unlimited
+    #[max_len(MAX_BALANCE_EXEMPTIONS)]
+    pub balance_exemptions: Vec<Pubkey>, // Token accounts not subject to max_wallet_balance
+    #[max_len(MAX_VELOCITY_EXEMPTIONS)]
+    pub velocity_exemptions: Vec<Pubkey>, // Source token accounts not subject to the velocity limits
+    pub bump: u8,
+}
+
+impl HookConfig {
+    // Starts at the previous hard-coded rate of 0.1% with no floor, cap or restrictions
+    pub fn new(mint: Pubkey, admin: Pubkey, bump: u8) -> Self {
+        Self {
+            mint,
+            admin,
+            pending_admin: None,
+            fee_bps: DEFAULT_FEE_BPS,
+            min_fee: 0,
+            max_fee: u64::MAX,
+            allowlist_enabled: false,
+            max_transfer_amount: 0,
+            daily_transfer_limit: 0,
+            max_wallet_balance: 0,
+            balance_exemptions: Vec::new(),
+            velocity_exemptions: Vec::new(),
+            bump,
+        }
+    }
+
+    pub fn fee_for(&self, amount: u64) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+        let fee = (amount as u128)
+            .checked_mul(self.fee_bps as u128)
+            .ok_or(HookError::Overflow)?
+            / MAX_FEE_BPS as u128;
+        Ok((fee as u64).max(self.min_fee).min(self.max_fee))
+    }
+
+    pub fn balance_allowed(&self, token_account: &Pubkey, balance: u64) -> bool {
+        self.max_wallet_balance == 0
+            || balance <= self.max_wallet_balance
+            || self.balance_exemptions.contains(token_account)
+    }
+
+    pub fn velocity_limited(&self, source: &Pubkey) -> bool {
+        (self.max_transfer_amount != 0 || self.daily_transfer_limit != 0)
+            && !self.velocity_exemptions.contains(source)
+    }
+}
+
+// Approval of one owner for one mint, seeded by [b"wallet-record", mint, owner].
+// Revoking closes the account, so existence plus expiry is the whole check.
+#[account]
+#[derive(InitSpace)]
+pub struct WalletRecord {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub expires_at: i64, // Unix timestamp; 0 never expires
+    pub bump: u8,
+}
+
+impl WalletRecord {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.expires_at == 0 || now < self.expires_at
+    }
+
+    // The address is checked by the caller's seeds constraint; a missing or foreign
+    // account means the owner was never approved or has been revoked
+    pub fn assert_approved(info: &AccountInfo, now: i64) -> Result<()> {
+        require!(info.owner == &crate::ID, HookError::WalletNotApproved);
+        let record = WalletRecord::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| HookError::WalletNotApproved)?;
+        require!(record.is_active(now), HookError::WalletApprovalExpired);
+        Ok(())
+    }
+}
+
+// Sanctioned owner for one mint, seeded by [b"blocked-wallet", mint, owner]
+#[account]
+#[derive(InitSpace)]
+pub struct BlockedWallet {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub blocked_at: i64,
+    pub bump: u8,
+}
+
+impl BlockedWallet {
+    pub const SEED: &'static [u8] = b"blocked-wallet";
+
+    // Address is checked by the caller's seeds constraint; unblocking closes the account
+    pub fn assert_not_blocked(info: &AccountInfo) -> Result<()> {
+        require!(
+            info.owner != &crate::ID || info.data_is_empty(),
+            HookError::WalletBlocked
+        );
+        Ok(())
+    }
+}
+
+// Outgoing volume of one owner for one mint, seeded by [b"transfer-stats", mint, owner]
+#[account]
+#[derive(InitSpace)]
+pub struct TransferStats {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub buckets: [u64; VELOCITY_BUCKETS], // Amount sent per hour, indexed by hour % 24
+    pub last_hour: i64, // Hour of the most recent transfer
+    pub bump: u8,
+}
+
+impl TransferStats {
+    // Clears buckets that have left the window, then adds `amount` if the trailing
+    // 24h total stays within `limit`
+    pub fn record(&mut self, now: i64, amount: u64, limit: u64) -> Result<()> {
+        let hour = now / VELOCITY_BUCKET_SECONDS;
+        let elapsed = hour.saturating_sub(self.last_hour);
+        if elapsed >= VELOCITY_BUCKETS as i64 {
+            self.buckets = [0; VELOCITY_BUCKETS];
+        } else {
+            for stale in (self.last_hour + 1)..=hour {
+                self.buckets[stale.rem_euclid(VELOCITY_BUCKETS as i64) as usize] = 0;
+            }
+        }
+        self.last_hour = self.last_hour.max(hour);
+
+        let sent = self
+            .buckets
+            .iter()
+            .try_fold(amount, |total, bucket| total.checked_add(*bucket))
+            .ok_or(HookError::Overflow)?;
+        require!(sent <= limit, HookError::DailyLimitExceeded);
+
+        let slot = &mut self.buckets[hour.rem_euclid(VELOCITY_BUCKETS as i64) as usize];
+        *slot = slot.checked_add(amount).ok_or(HookError::Overflow)?;
+        Ok(())
+    }
+
+    // Address is checked by the caller's seeds constraint
+    pub fn record_transfer(info: &AccountInfo, now: i64, amount: u64, limit: u64) -> Result<()> {
+        require!(info.owner == &crate::ID, HookError::TransferStatsMissing);
+        let mut stats = TransferStats::try_deserialize(&mut &info.try_borrow_data()?[..])
+            .map_err(|_| HookError::TransferStatsMissing)?;
+        stats.record(now, amount, limit)?;
+        stats.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+}
+
+// Lockup of one owner's allocation, seeded by [b"vesting", mint, owner]. Nothing unlocks
+// before the cliff; from then on the unlocked share grows linearly from start to end.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingSchedule {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub total_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    pub fn locked_amount(&self, now: i64) -> u64 {
+        if now < self.cliff_ts {
+            return self.total_amount;
+        }
+        if now >= self.end_ts {
+            return 0;
+        }
+        let elapsed = (now - self.start_ts) as u128;
+        let duration = (self.end_ts - self.start_ts) as u128;
+        let unlocked = self.total_amount as u128 * elapsed / duration;
+        self.total_amount - unlocked as u64
+    }
+
+    // Address is checked by the caller's seeds constraint; no account means no lockup.
+    // Applies to every account the owner sends from, so allocations belong in one account.
+    pub fn assert_unlocked(info: &AccountInfo, now: i64, balance_after: u64) -> Result<()> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(());
+        }
+        let schedule = VestingSchedule::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(balance_after >= schedule.locked_amount(now), HookError::TokensLocked);
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{HookConfig, TransferStats, VELOCITY_BUCKETS};
+
+// Permissionless: the hook cannot create accounts during a transfer, so senders (or
+// anyone on their behalf) create their stats account before the first limited transfer
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitTransferStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"hook-config", hook_config.mint.as_ref()], bump = hook_config.bump)]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TransferStats::INIT_SPACE,
+        seeds = [b"transfer-stats", hook_config.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub transfer_stats: Account<'info, TransferStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitTransferStats<'info> {
+    pub fn init_transfer_stats(&mut self, owner: Pubkey, bump: u8) -> Result<()> {
+        self.transfer_stats.set_inner(TransferStats {
+            mint: self.hook_config.mint,
+            owner,
+            buckets: [0; VELOCITY_BUCKETS],
+            last_hour: 0,
+            bump,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::HookError, events::VestingScheduleCreated, HookConfig, VestingSchedule};
+
+// The issuer (hook admin) sets up lockups; tokens are delivered to the owner separately
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct CreateVestingSchedule<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"hook-config", hook_config.mint.as_ref()],
+        bump = hook_config.bump,
+        has_one = admin @ HookError::Unauthorized
+    )]
+    pub hook_config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VestingSchedule::INIT_SPACE,
+        seeds = [b"vesting", hook_config.mint.as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateVestingSchedule<'info> {
+    pub fn create_vesting_schedule(
+        &mut self,
+        owner: Pubkey,
+        total_amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+        bump: u8,
+    ) -> Result<()> {
+        require!(
+            start_ts <= cliff_ts && cliff_ts <= end_ts && start_ts < end_ts,
+            HookError::InvalidVestingSchedule
+        );
+
+        let mint = self.hook_config.mint;
+        self.vesting_schedule.set_inner(VestingSchedule {
+            mint,
+            owner,
+            total_amount,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            bump,
+        });
+        emit!(VestingScheduleCreated {
+            mint,
+            owner,
+            total_amount,
+            start_ts,
+            cliff_ts,
+            end_ts,
+        });
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ import { ASSOCIATED_TOKEN_PROGRAM_ID, createApproveInstruction, createAssociated
 import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
 import { BN, min } from "bn.js";
 import { bs58 } from "@coral-xyz/anchor/dist/cjs/utils/bytes";
+import { ensureHookApproved, hookRegistryAddress } from "./helpers";
 
 describe("amm", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -15,7 +16,12 @@ describe("amm", () => {
   const transferHookProgram = anchor.workspace.transferHook as Program<TransferHook>;
 
     const decimals = 9;
-  beforeEach(async () => {
+
+  // Pools for hooked mints need the transfer-hook program in the global registry
+  const hookRegistry = hookRegistryAddress(program);
+
+  before(async () => {
+    await ensureHookApproved(program, transferHookProgram.programId);
   });
 
   it("Initialize pool", async () => {
@@ -41,9 +47,6 @@ describe("amm", () => {
       TOKEN_2022_PROGRAM_ID,
       ASSOCIATED_TOKEN_PROGRAM_ID
     );
-     const [solvault] = PublicKey.findProgramAddressSync(
-        [Buffer.from("sol_vault"), config.toBuffer()], program.programId
-      )
       
      const [extramint] = PublicKey.findProgramAddressSync(
       [Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()],
@@ -59,7 +62,7 @@ describe("amm", () => {
            const wsolVault=getAssociatedTokenAddressSync(mintB.publicKey,config,true,TOKEN_2022_PROGRAM_ID);
      const senderwsol=getAssociatedTokenAddressSync(mintB.publicKey,wallet.publicKey,false,TOKEN_2022_PROGRAM_ID)
      const [delegatePda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("delegate")],
+      [Buffer.from("delegate"), mint.publicKey.toBuffer()],
       transferHookProgram.programId
      );
      const delegateWsolAta = getAssociatedTokenAddressSync(
@@ -134,24 +137,20 @@ describe("amm", () => {
     );
     console.log("mint keypair",mint.publicKey.toString())
     console.log(`Transaction Signature: ${txSig1}`);
-    // Generate a dummy ExtraAccountMetaList PDA for the AMM program (not used)
-    const [ammExtraList] = PublicKey.findProgramAddressSync(
-      [Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()],
-      program.programId
-    );
     
-    const tx = await program.methods.initialize(seed, fee, wallet.publicKey).accountsStrict({
+    const tx = await program.methods.initialize(seed, fee, wallet.publicKey, 0).accountsStrict({
       signer: wallet.publicKey,
-      extraAccountMetaList: ammExtraList,
-      mint: mint.publicKey,
-      wsolMint: mintB.publicKey,
+      mintA: mint.publicKey,
+      mintB: mintB.publicKey,
       lpToken: lptoken,
-      vault,
-      solVault: solvault,
-      wsolVault: wsolVault,
+      vaultA: vault,
+      vaultB: wsolVault,
       config: config,
+      hookRegistry,
       systemProgram: SYSTEM_PROGRAM_ID,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
     })
     .signers([wallet]).rpc();
@@ -253,24 +252,31 @@ describe("amm", () => {
     console.log("- senderwsol:", senderwsol.toString());
     console.log("- extramint (ExtraAccountMetaList):", extramint.toString());
 
-    const solAmount = new anchor.BN(2 * 10**9); // 2 SOL
-    const tokenAmount = new anchor.BN(10); // skip token transfer to avoid transfer-hook extra accounts
-    const solnmax=new anchor.BN(1000);
-    const tokenmax=new anchor.BN(1000);
+    // First deposit: LP out is sqrt(tokenmax * mintbmax), less the locked minimum liquidity
+    const minLp = new anchor.BN(10);
+    const mintbmax=new anchor.BN(2 * 10**9);
+    const tokenmax=new anchor.BN(10 * 10**9);
+    const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 60);
+    const [lockedLiquidity] = PublicKey.findProgramAddressSync([Buffer.from("locked_liquidity"), config.toBuffer()], program.programId);
+    const lockedLp = getAssociatedTokenAddressSync(lptoken, lockedLiquidity, true, TOKEN_2022_PROGRAM_ID);
     console.log("vaulet",vault.toString());
-    const tx3 = await program.methods.deposit(solAmount, tokenAmount, solnmax, tokenmax).accountsStrict({
+    const tx3 = await program.methods.deposit(minLp, tokenmax, mintbmax, expiration).accountsStrict({
       signer: wallet.publicKey,
-      mintx: mint.publicKey,
-      minty: mintB.publicKey,
-      userX: sourceTokenAccount,
-      userY: senderwsol,
+      mintA: mint.publicKey,
+      mintB: mintB.publicKey,
+      userA: sourceTokenAccount,
+      userB: senderwsol,
       userLp: userlp,
       lpToken: lptoken,
-      vaultX: vault,
-      vaultY: wsolVault,
+      lockedLiquidity,
+      lockedLp,
+      vaultA: vault,
+      vaultB: wsolVault,
       config: config,
       systemProgram: SYSTEM_PROGRAM_ID,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
     }).signers([wallet]).rpc();
     
@@ -302,7 +308,6 @@ describe("amm", () => {
     const mint = Keypair.generate();
     const mintB = Keypair.generate();
     const vault = getAssociatedTokenAddressSync(mint.publicKey, config, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID);
-    const [solvault] = PublicKey.findProgramAddressSync([Buffer.from("sol_vault"), config.toBuffer()], program.programId);
     const wsolVault = getAssociatedTokenAddressSync(mintB.publicKey, config, true, TOKEN_2022_PROGRAM_ID);
     
     const extensions = [ExtensionType.TransferHook];
@@ -334,20 +339,19 @@ describe("amm", () => {
     createMintTx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
     await sendAndConfirmTransaction(provider.connection, createMintTx, [wallet, mint, mintB]);
     
-    const [ammExtraList] = PublicKey.findProgramAddressSync([Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()], program.programId);
-    
-    await program.methods.initialize(seed, 2, wallet.publicKey).accountsStrict({
+    await program.methods.initialize(seed, 2, wallet.publicKey, 0).accountsStrict({
       signer: wallet.publicKey,
-      extraAccountMetaList: ammExtraList,
-      mint: mint.publicKey,
-      wsolMint: mintB.publicKey,
+      mintA: mint.publicKey,
+      mintB: mintB.publicKey,
       lpToken: lptoken,
-      vault,
-      solVault: solvault,
-      wsolVault: wsolVault,
+      vaultA: vault,
+      vaultB: wsolVault,
       config: config,
+      hookRegistry,
       systemProgram: SYSTEM_PROGRAM_ID,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
     }).signers([wallet]).rpc();
   });
@@ -368,7 +372,6 @@ describe("amm", () => {
     const mint = Keypair.generate();
     const mintB = Keypair.generate();
     const vault = getAssociatedTokenAddressSync(mint.publicKey, config, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID);
-    const [solvault] = PublicKey.findProgramAddressSync([Buffer.from("sol_vault"), config.toBuffer()], program.programId);
     const wsolVault = getAssociatedTokenAddressSync(mintB.publicKey, config, true, TOKEN_2022_PROGRAM_ID);
     
     const extensions = [ExtensionType.TransferHook];
@@ -400,20 +403,19 @@ describe("amm", () => {
     createMintTx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
     await sendAndConfirmTransaction(provider.connection, createMintTx, [wallet, mint, mintB]);
     
-    const [ammExtraList] = PublicKey.findProgramAddressSync([Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()], program.programId);
-    
-    await program.methods.initialize(seed, 2, wallet.publicKey).accountsStrict({
+    await program.methods.initialize(seed, 2, wallet.publicKey, 0).accountsStrict({
       signer: wallet.publicKey,
-      extraAccountMetaList: ammExtraList,
-      mint: mint.publicKey,
-      wsolMint: mintB.publicKey,
+      mintA: mint.publicKey,
+      mintB: mintB.publicKey,
       lpToken: lptoken,
-      vault,
-      solVault: solvault,
-      wsolVault: wsolVault,
+      vaultA: vault,
+      vaultB: wsolVault,
       config: config,
+      hookRegistry,
       systemProgram: SYSTEM_PROGRAM_ID,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
     }).signers([wallet]).rpc();
   });
@@ -434,7 +436,6 @@ describe("amm", () => {
     const mint = Keypair.generate();
     const mintB = Keypair.generate();
     const vault = getAssociatedTokenAddressSync(mint.publicKey, config, true, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID);
-    const [solvault] = PublicKey.findProgramAddressSync([Buffer.from("sol_vault"), config.toBuffer()], program.programId);
     const wsolVault = getAssociatedTokenAddressSync(mintB.publicKey, config, true, TOKEN_2022_PROGRAM_ID);
     
     const extensions = [ExtensionType.TransferHook];
@@ -466,20 +467,19 @@ describe("amm", () => {
     createMintTx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
     await sendAndConfirmTransaction(provider.connection, createMintTx, [wallet, mint, mintB]);
     
-    const [ammExtraList] = PublicKey.findProgramAddressSync([Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()], program.programId);
-    
-    await program.methods.initialize(seed, 2, wallet.publicKey).accountsStrict({
+    await program.methods.initialize(seed, 2, wallet.publicKey, 0).accountsStrict({
       signer: wallet.publicKey,
-      extraAccountMetaList: ammExtraList,
-      mint: mint.publicKey,
-      wsolMint: mintB.publicKey,
+      mintA: mint.publicKey,
+      mintB: mintB.publicKey,
       lpToken: lptoken,
-      vault,
-      solVault: solvault,
-      wsolVault: wsolVault,
+      vaultA: vault,
+      vaultB: wsolVault,
       config: config,
+      hookRegistry,
       systemProgram: SYSTEM_PROGRAM_ID,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
     }).signers([wallet]).rpc();
   });
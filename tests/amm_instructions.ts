@@ -0,0 +1,759 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Amm } from "../target/types/amm";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, TransactionInstruction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  AccountState,
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  createInitializeDefaultAccountStateInstruction,
+  createInitializeMintCloseAuthorityInstruction,
+  createInitializeMintInstruction,
+  createInitializeNonTransferableMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  transferChecked,
+} from "@solana/spl-token";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import { ensureHookRegistry, hookRegistryAddress } from "./helpers";
+
+// Pool instructions on a plain Token-2022 pair: no hook and no transfer fee, so every
+// balance change can be checked exactly
+describe("amm pool instructions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.amm as Program<Amm>;
+  const connection = provider.connection;
+
+  const decimals = 6;
+  const feeBps = 30;
+  const authority = Keypair.generate();
+  const user = Keypair.generate();
+
+  const seed = new BN(Date.now());
+  const [config] = PublicKey.findProgramAddressSync([Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)], program.programId);
+  const [lpToken] = PublicKey.findProgramAddressSync([Buffer.from("lp"), config.toBuffer()], program.programId);
+  const [lockedLiquidity] = PublicKey.findProgramAddressSync([Buffer.from("locked_liquidity"), config.toBuffer()], program.programId);
+  const hookRegistry = hookRegistryAddress(program);
+  const userLp = getAssociatedTokenAddressSync(lpToken, user.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const lockedLp = getAssociatedTokenAddressSync(lpToken, lockedLiquidity, true, TOKEN_2022_PROGRAM_ID);
+
+  let mintA: PublicKey;
+  let mintB: PublicKey;
+  let vaultA: PublicKey;
+  let vaultB: PublicKey;
+  let userA: PublicKey;
+  let userB: PublicKey;
+
+  const deadline = () => new BN(Math.floor(Date.now() / 1000) + 60);
+  const balance = async (account: PublicKey) =>
+    Number((await getAccount(connection, account, "confirmed", TOKEN_2022_PROGRAM_ID)).amount);
+  const lpSupply = async () => new BN((await getMint(connection, lpToken, "confirmed", TOKEN_2022_PROGRAM_ID)).supply.toString());
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+    } catch (err) {
+      expect(String(err)).to.include(code);
+      return;
+    }
+    expect.fail(`expected ${code}`);
+  };
+
+  const poolAccounts = () => ({
+    signer: user.publicKey,
+    mintA,
+    mintB,
+    userA,
+    userB,
+    lpToken,
+    vaultA,
+    vaultB,
+    config,
+    systemProgram: SystemProgram.programId,
+    tokenProgramA: TOKEN_2022_PROGRAM_ID,
+    tokenProgramB: TOKEN_2022_PROGRAM_ID,
+  });
+  const withdrawAccounts = () => ({
+    ...poolAccounts(),
+    userLp,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  });
+  const depositAccounts = () => ({ ...withdrawAccounts(), lockedLiquidity, lockedLp });
+
+  before(async () => {
+    for (const wallet of [authority, user]) {
+      const sig = await connection.requestAirdrop(wallet.publicKey, 10 * LAMPORTS_PER_SOL);
+      await connection.confirmTransaction(sig, "confirmed");
+    }
+
+    await ensureHookRegistry(program);
+
+    mintA = await createMint(connection, authority, authority.publicKey, null, decimals, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    mintB = await createMint(connection, authority, authority.publicKey, null, decimals, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    vaultA = getAssociatedTokenAddressSync(mintA, config, true, TOKEN_2022_PROGRAM_ID);
+    vaultB = getAssociatedTokenAddressSync(mintB, config, true, TOKEN_2022_PROGRAM_ID);
+    userA = (await getOrCreateAssociatedTokenAccount(connection, user, mintA, user.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    userB = (await getOrCreateAssociatedTokenAccount(connection, user, mintB, user.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    await mintTo(connection, authority, mintA, userA, authority, 1_000_000 * 10 ** decimals, [], undefined, TOKEN_2022_PROGRAM_ID);
+    await mintTo(connection, authority, mintB, userB, authority, 1_000_000 * 10 ** decimals, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods.initialize(seed, feeBps, authority.publicKey, 0).accountsStrict({
+      signer: authority.publicKey,
+      mintA,
+      mintB,
+      lpToken,
+      vaultA,
+      vaultB,
+      config,
+      hookRegistry,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    }).signers([authority]).rpc();
+
+    const seedAmount = new BN(100_000 * 10 ** decimals);
+    await program.methods
+      .deposit(new BN(1), seedAmount, seedAmount, deadline())
+      .accountsStrict(depositAccounts())
+      .signers([user])
+      .rpc();
+  });
+
+  it("swap_exact_out pays exactly amount_out and bounds the input by max_in", async () => {
+    const amountOut = new BN(1_000 * 10 ** decimals);
+    const pool = await program.account.config.fetch(config);
+    // ceil(x * dy / (y - dy)), grossed up by the fee and rounded up again
+    const remaining = pool.reserveB.sub(amountOut);
+    const afterFee = pool.reserveA.mul(amountOut).add(remaining).subn(1).div(remaining);
+    const expectedIn = afterFee.muln(10_000).addn(10_000 - feeBps - 1).divn(10_000 - feeBps);
+
+    await expectError(
+      program.methods
+        .swapExactOut(amountOut, expectedIn.subn(1), true, deadline())
+        .accountsStrict(poolAccounts())
+        .signers([user])
+        .rpc(),
+      "SlippageExceded"
+    );
+
+    const [userABefore, userBBefore] = [await balance(userA), await balance(userB)];
+    await program.methods
+      .swapExactOut(amountOut, expectedIn, true, deadline())
+      .accountsStrict(poolAccounts())
+      .signers([user])
+      .rpc();
+
+    expect(userABefore - (await balance(userA))).to.equal(expectedIn.toNumber());
+    expect((await balance(userB)) - userBBefore).to.equal(amountOut.toNumber());
+    const after = await program.account.config.fetch(config);
+    expect(after.reserveA.toString()).to.equal(pool.reserveA.add(expectedIn).toString());
+    expect(after.reserveB.toString()).to.equal(remaining.toString());
+  });
+
+  it("rejects a swap past its expiration", async () => {
+    // An hour back, so a lagging validator clock cannot still be before it
+    const expired = new BN(Math.floor(Date.now() / 1000) - 3600);
+    await expectError(
+      program.methods
+        .swap(new BN(10 * 10 ** decimals), true, new BN(1), expired)
+        .accountsStrict(poolAccounts())
+        .signers([user])
+        .rpc(),
+      "OfferExpired"
+    );
+  });
+
+  it("rejects user token accounts the signer does not own", async () => {
+    // The authority's account for mint A stands in for someone else's
+    const otherA = (await getOrCreateAssociatedTokenAccount(connection, authority, mintA, authority.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    await expectError(
+      program.methods
+        .swap(new BN(10 * 10 ** decimals), false, new BN(1), deadline())
+        .accountsStrict({ ...poolAccounts(), userA: otherA })
+        .signers([user])
+        .rpc(),
+      "ConstraintTokenOwner"
+    );
+  });
+
+  it("collect_protocol_fees sends the accrued share to the treasury, authority only", async () => {
+    await program.methods.setProtocolFeeShare(5_000).accountsStrict({ authority: authority.publicKey, config }).signers([authority]).rpc();
+    await program.methods
+      .swap(new BN(1_000 * 10 ** decimals), true, new BN(0), deadline())
+      .accountsStrict(poolAccounts())
+      .signers([user])
+      .rpc();
+
+    const accrued = await program.account.config.fetch(config);
+    expect(accrued.protocolFeesA.toNumber()).to.be.greaterThan(0);
+    expect(await balance(vaultA)).to.equal(accrued.reserveA.add(accrued.protocolFeesA).toNumber());
+
+    const treasury = Keypair.generate();
+    const treasuryA = (await getOrCreateAssociatedTokenAccount(connection, authority, mintA, treasury.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    const treasuryB = (await getOrCreateAssociatedTokenAccount(connection, authority, mintB, treasury.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    const collectAccounts = (signer: PublicKey) => ({
+      authority: signer,
+      config,
+      mintA,
+      mintB,
+      vaultA,
+      vaultB,
+      treasury: treasury.publicKey,
+      treasuryA,
+      treasuryB,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
+    });
+
+    await expectError(
+      program.methods.collectProtocolFees().accountsStrict(collectAccounts(user.publicKey)).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+
+    await program.methods.collectProtocolFees().accountsStrict(collectAccounts(authority.publicKey)).signers([authority]).rpc();
+    expect(await balance(treasuryA)).to.equal(accrued.protocolFeesA.toNumber());
+    expect(await balance(treasuryB)).to.equal(accrued.protocolFeesB.toNumber());
+    const collected = await program.account.config.fetch(config);
+    expect(collected.protocolFeesA.toNumber()).to.equal(0);
+    expect(collected.protocolFeesB.toNumber()).to.equal(0);
+    expect(await balance(vaultA)).to.equal(collected.reserveA.toNumber());
+  });
+
+  it("deposit_by_amounts pulls the largest balanced deposit and enforces min_lp_out", async () => {
+    const desiredA = new BN(1_000 * 10 ** decimals);
+    const desiredB = new BN(10_000 * 10 ** decimals);
+    const pool = await program.account.config.fetch(config);
+    const supply = await lpSupply();
+    // A binds; LP rounds down and the amounts pulled round up
+    const lpOut = BN.min(desiredA.mul(supply).div(pool.reserveA), desiredB.mul(supply).div(pool.reserveB));
+    const ceilDiv = (n: BN, d: BN) => n.add(d).subn(1).div(d);
+    const expectedA = ceilDiv(lpOut.mul(pool.reserveA), supply);
+    const expectedB = ceilDiv(lpOut.mul(pool.reserveB), supply);
+
+    await expectError(
+      program.methods
+        .depositByAmounts(desiredA, desiredB, lpOut.addn(1), deadline())
+        .accountsStrict(depositAccounts())
+        .signers([user])
+        .rpc(),
+      "SlippageExceded"
+    );
+
+    const [userABefore, userBBefore, lpBefore] = [await balance(userA), await balance(userB), await balance(userLp)];
+    await program.methods
+      .depositByAmounts(desiredA, desiredB, lpOut, deadline())
+      .accountsStrict(depositAccounts())
+      .signers([user])
+      .rpc();
+
+    expect((await balance(userLp)) - lpBefore).to.equal(lpOut.toNumber());
+    expect(userABefore - (await balance(userA))).to.equal(expectedA.toNumber());
+    expect(userBBefore - (await balance(userB))).to.equal(expectedB.toNumber());
+    expect(expectedB.lt(desiredB)).to.be.true;
+  });
+
+  it("deposit_single_sided zaps one asset into LP and enforces min_lp_out", async () => {
+    const amountIn = new BN(1_000 * 10 ** decimals);
+    const pool = await program.account.config.fetch(config);
+    // Minting as if all of amount_in were matched is out of reach once part of it is swapped
+    const unreachableLp = amountIn.mul(await lpSupply()).div(pool.reserveA);
+
+    await expectError(
+      program.methods
+        .depositSingleSided(true, amountIn, unreachableLp, deadline())
+        .accountsStrict(depositAccounts())
+        .signers([user])
+        .rpc(),
+      "SlippageExceded"
+    );
+
+    const [userABefore, userBBefore, lpBefore] = [await balance(userA), await balance(userB), await balance(userLp)];
+    await program.methods
+      .depositSingleSided(true, amountIn, new BN(1), deadline())
+      .accountsStrict(depositAccounts())
+      .signers([user])
+      .rpc();
+
+    const spentA = userABefore - (await balance(userA));
+    const minted = (await balance(userLp)) - lpBefore;
+    expect(spentA).to.be.greaterThan(0);
+    expect(spentA).to.be.at.most(amountIn.toNumber());
+    expect(await balance(userB)).to.equal(userBBefore);
+    // Roughly half the input is swapped, so about half the unreachable LP is minted
+    expect(minted).to.be.greaterThan(unreachableLp.toNumber() * 0.45);
+    expect(minted).to.be.lessThan(unreachableLp.toNumber());
+
+    const after = await program.account.config.fetch(config);
+    expect(await balance(vaultA)).to.equal(after.reserveA.add(after.protocolFeesA).toNumber());
+    expect(await balance(vaultB)).to.equal(after.reserveB.add(after.protocolFeesB).toNumber());
+  });
+
+  it("withdraw_single_sided pays the whole position in one asset and enforces min_out", async () => {
+    const burnAmount = new BN(await balance(userLp)).divn(10);
+    const pool = await program.account.config.fetch(config);
+    const supply = await lpSupply();
+    const shareA = pool.reserveA.mul(burnAmount).div(supply);
+    // Both legs valued at the pool price with no fee or price impact
+    const unreachableOut = shareA.muln(2);
+
+    await expectError(
+      program.methods
+        .withdrawSingleSided(burnAmount, true, unreachableOut, deadline())
+        .accountsStrict(withdrawAccounts())
+        .signers([user])
+        .rpc(),
+      "SlippageExceded"
+    );
+
+    const [userABefore, userBBefore, lpBefore] = [await balance(userA), await balance(userB), await balance(userLp)];
+    await program.methods
+      .withdrawSingleSided(burnAmount, true, shareA, deadline())
+      .accountsStrict(withdrawAccounts())
+      .signers([user])
+      .rpc();
+
+    const receivedA = (await balance(userA)) - userABefore;
+    expect(lpBefore - (await balance(userLp))).to.equal(burnAmount.toNumber());
+    expect(await balance(userB)).to.equal(userBBefore);
+    expect(receivedA).to.be.greaterThan(shareA.toNumber());
+    expect(receivedA).to.be.lessThan(unreachableOut.toNumber());
+
+    const after = await program.account.config.fetch(config);
+    expect(await balance(vaultA)).to.equal(after.reserveA.add(after.protocolFeesA).toNumber());
+    expect(await balance(vaultB)).to.equal(after.reserveB.add(after.protocolFeesB).toNumber());
+  });
+
+  it("prices swaps net of a Token-2022 transfer fee on either leg", async () => {
+    const transferFeeBps = 100;
+    const transferFee = (amount: BN) => amount.muln(transferFeeBps).addn(9_999).divn(10_000);
+
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          authority.publicKey,
+          authority.publicKey,
+          transferFeeBps,
+          BigInt(1_000_000 * 10 ** decimals),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(feeMint.publicKey, decimals, authority.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [authority, feeMint]
+    );
+    const userFee = (await getOrCreateAssociatedTokenAccount(connection, user, feeMint.publicKey, user.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    await mintTo(connection, authority, feeMint.publicKey, userFee, authority, 1_000_000 * 10 ** decimals, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    // Side A carries the fee, side B is the plain mint from the main pool
+    const feeSeed = seed.addn(1);
+    const [feeConfig] = PublicKey.findProgramAddressSync([Buffer.from("config"), feeSeed.toArrayLike(Buffer, "le", 8)], program.programId);
+    const [feeLpToken] = PublicKey.findProgramAddressSync([Buffer.from("lp"), feeConfig.toBuffer()], program.programId);
+    const [feeLockedLiquidity] = PublicKey.findProgramAddressSync([Buffer.from("locked_liquidity"), feeConfig.toBuffer()], program.programId);
+    const feeVaultA = getAssociatedTokenAddressSync(feeMint.publicKey, feeConfig, true, TOKEN_2022_PROGRAM_ID);
+    const feeVaultB = getAssociatedTokenAddressSync(mintB, feeConfig, true, TOKEN_2022_PROGRAM_ID);
+    const feePoolAccounts = () => ({
+      ...poolAccounts(),
+      mintA: feeMint.publicKey,
+      userA: userFee,
+      lpToken: feeLpToken,
+      vaultA: feeVaultA,
+      vaultB: feeVaultB,
+      config: feeConfig,
+    });
+
+    await program.methods.initialize(feeSeed, feeBps, authority.publicKey, 0).accountsStrict({
+      signer: authority.publicKey,
+      mintA: feeMint.publicKey,
+      mintB,
+      lpToken: feeLpToken,
+      vaultA: feeVaultA,
+      vaultB: feeVaultB,
+      config: feeConfig,
+      hookRegistry,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    }).signers([authority]).rpc();
+
+    const seedAmount = new BN(100_000 * 10 ** decimals);
+    await program.methods
+      .deposit(new BN(1), seedAmount, seedAmount, deadline())
+      .accountsStrict({
+        ...feePoolAccounts(),
+        userLp: getAssociatedTokenAddressSync(feeLpToken, user.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        lockedLiquidity: feeLockedLiquidity,
+        lockedLp: getAssociatedTokenAddressSync(feeLpToken, feeLockedLiquidity, true, TOKEN_2022_PROGRAM_ID),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+    // The reserve is what reached the vault, not what the user sent
+    let pool = await program.account.config.fetch(feeConfig);
+    expect(pool.reserveA.toString()).to.equal(seedAmount.sub(transferFee(seedAmount)).toString());
+    expect(await balance(feeVaultA)).to.equal(pool.reserveA.toNumber());
+
+    // Fee mint in: only the post-fee amount enters the curve
+    const amountIn = new BN(1_000 * 10 ** decimals);
+    await program.methods
+      .swap(amountIn, true, new BN(1), deadline())
+      .accountsStrict(feePoolAccounts())
+      .signers([user])
+      .rpc();
+    const afterSwap = await program.account.config.fetch(feeConfig);
+    expect(afterSwap.reserveA.sub(pool.reserveA).toString()).to.equal(amountIn.sub(transferFee(amountIn)).toString());
+    expect(await balance(feeVaultA)).to.equal(afterSwap.reserveA.toNumber());
+
+    // Fee mint out: an input bound that ignores the withheld fee is too small
+    pool = afterSwap;
+    const amountOut = new BN(500 * 10 ** decimals);
+    const remaining = pool.reserveA.sub(amountOut);
+    const naiveIn = pool.reserveB.mul(amountOut).add(remaining).subn(1).div(remaining)
+      .muln(10_000).addn(10_000 - feeBps - 1).divn(10_000 - feeBps);
+    await expectError(
+      program.methods
+        .swapExactOut(amountOut, naiveIn, false, deadline())
+        .accountsStrict(feePoolAccounts())
+        .signers([user])
+        .rpc(),
+      "SlippageExceded"
+    );
+
+    const [userFeeBefore, vaultBefore] = [await balance(userFee), await balance(feeVaultA)];
+    await program.methods
+      .swapExactOut(amountOut, naiveIn.muln(2), false, deadline())
+      .accountsStrict(feePoolAccounts())
+      .signers([user])
+      .rpc();
+    const paidOut = new BN(vaultBefore - (await balance(feeVaultA)));
+    expect((await balance(userFee)) - userFeeBefore).to.equal(amountOut.toNumber());
+    expect(paidOut.sub(amountOut).toString()).to.equal(transferFee(paidOut).toString());
+    expect((await program.account.config.fetch(feeConfig)).reserveA.toNumber()).to.equal(await balance(feeVaultA));
+  });
+
+  it("only pools risky mint extensions the creator opted in to", async () => {
+    const EXT_PERMANENT_DELEGATE = 1 << 2;
+    const EXT_MINT_CLOSE_AUTHORITY = 1 << 3;
+    const EXT_DEFAULT_FROZEN = 1 << 4;
+    const EXT_RISKY = EXT_PERMANENT_DELEGATE | EXT_MINT_CLOSE_AUTHORITY | EXT_DEFAULT_FROZEN;
+    const createExtensionMint = async (extension: ExtensionType, init: (mint: PublicKey) => TransactionInstruction) => {
+      const extMint = Keypair.generate();
+      const mintLen = getMintLen([extension]);
+      await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: authority.publicKey,
+            newAccountPubkey: extMint.publicKey,
+            space: mintLen,
+            lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          init(extMint.publicKey),
+          createInitializeMintInstruction(extMint.publicKey, decimals, authority.publicKey, authority.publicKey, TOKEN_2022_PROGRAM_ID)
+        ),
+        [authority, extMint]
+      );
+      return extMint.publicKey;
+    };
+    // Each attempt uses a fresh seed; side B is the plain mint from the main pool
+    let nextSeed = seed.addn(10);
+    const initializePool = (extMint: PublicKey, allowedExtensions: number) => {
+      const poolSeed = (nextSeed = nextSeed.addn(1));
+      const [poolConfig] = PublicKey.findProgramAddressSync([Buffer.from("config"), poolSeed.toArrayLike(Buffer, "le", 8)], program.programId);
+      const [poolLpToken] = PublicKey.findProgramAddressSync([Buffer.from("lp"), poolConfig.toBuffer()], program.programId);
+      const call = program.methods.initialize(poolSeed, feeBps, authority.publicKey, allowedExtensions).accountsStrict({
+        signer: authority.publicKey,
+        mintA: extMint,
+        mintB,
+        lpToken: poolLpToken,
+        vaultA: getAssociatedTokenAddressSync(extMint, poolConfig, true, TOKEN_2022_PROGRAM_ID),
+        vaultB: getAssociatedTokenAddressSync(mintB, poolConfig, true, TOKEN_2022_PROGRAM_ID),
+        config: poolConfig,
+        hookRegistry,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      }).signers([authority]).rpc();
+      return { call, poolConfig };
+    };
+
+    const risky = [
+      {
+        flag: EXT_PERMANENT_DELEGATE,
+        mint: await createExtensionMint(ExtensionType.PermanentDelegate, mint =>
+          createInitializePermanentDelegateInstruction(mint, authority.publicKey, TOKEN_2022_PROGRAM_ID)),
+      },
+      {
+        flag: EXT_MINT_CLOSE_AUTHORITY,
+        mint: await createExtensionMint(ExtensionType.MintCloseAuthority, mint =>
+          createInitializeMintCloseAuthorityInstruction(mint, authority.publicKey, TOKEN_2022_PROGRAM_ID)),
+      },
+      {
+        flag: EXT_DEFAULT_FROZEN,
+        mint: await createExtensionMint(ExtensionType.DefaultAccountState, mint =>
+          createInitializeDefaultAccountStateInstruction(mint, AccountState.Frozen, TOKEN_2022_PROGRAM_ID)),
+      },
+    ];
+    for (const { flag, mint } of risky) {
+      await expectError(initializePool(mint, 0).call, "MintExtensionNotAllowed");
+      // Opting in to a different risky extension does not cover this one
+      await expectError(initializePool(mint, EXT_RISKY & ~flag).call, "MintExtensionNotAllowed");
+      const { call, poolConfig } = initializePool(mint, flag);
+      await call;
+      expect((await program.account.config.fetch(poolConfig)).extensionsA).to.equal(flag);
+    }
+
+    // Non-transferable mints can never be pooled, whatever the opt-in
+    const soulbound = await createExtensionMint(ExtensionType.NonTransferable, mint =>
+      createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID));
+    await expectError(initializePool(soulbound, 0xffff).call, "NonTransferableMint");
+  });
+
+  it("update_fee is gated on the pool authority", async () => {
+    await expectError(
+      program.methods.updateFee(25).accountsStrict({ authority: user.publicKey, config }).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+    await expectError(
+      program.methods.updateFee(10_000).accountsStrict({ authority: authority.publicKey, config }).signers([authority]).rpc(),
+      "InvalidFee"
+    );
+
+    await program.methods.updateFee(25).accountsStrict({ authority: authority.publicKey, config }).signers([authority]).rpc();
+    expect((await program.account.config.fetch(config)).fee).to.equal(25);
+    await program.methods.updateFee(feeBps).accountsStrict({ authority: authority.publicKey, config }).signers([authority]).rpc();
+  });
+
+  it("hands authority over in two steps, with cancel and a wrong-signer accept", async () => {
+    const newAuthority = Keypair.generate();
+    const adminAccounts = (signer: Keypair) => ({ authority: signer.publicKey, config });
+    const accept = (signer: Keypair) =>
+      program.methods.acceptAuthority().accountsStrict({ pendingAuthority: signer.publicKey, config }).signers([signer]).rpc();
+
+    await expectError(
+      program.methods.proposeAuthority(user.publicKey).accountsStrict(adminAccounts(user)).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+
+    await program.methods.proposeAuthority(newAuthority.publicKey).accountsStrict(adminAccounts(authority)).signers([authority]).rpc();
+    expect((await program.account.config.fetch(config)).pendingAuthority?.toBase58()).to.equal(newAuthority.publicKey.toBase58());
+    await expectError(accept(user), "InvalidPendingAuthority");
+
+    await program.methods.cancelAuthorityTransfer().accountsStrict(adminAccounts(authority)).signers([authority]).rpc();
+    expect((await program.account.config.fetch(config)).pendingAuthority).to.be.null;
+    await expectError(accept(newAuthority), "NoPendingAuthority");
+
+    await program.methods.proposeAuthority(newAuthority.publicKey).accountsStrict(adminAccounts(authority)).signers([authority]).rpc();
+    await accept(newAuthority);
+    const handedOver = await program.account.config.fetch(config);
+    expect(handedOver.authority?.toBase58()).to.equal(newAuthority.publicKey.toBase58());
+    expect(handedOver.pendingAuthority).to.be.null;
+    await expectError(
+      program.methods.updateFee(25).accountsStrict(adminAccounts(authority)).signers([authority]).rpc(),
+      "InvalidAuthority"
+    );
+
+    // Hand it back for the remaining tests
+    await program.methods.proposeAuthority(authority.publicKey).accountsStrict(adminAccounts(newAuthority)).signers([newAuthority]).rpc();
+    await accept(authority);
+  });
+
+  it("lets the guardian pause but never unpause or change fees", async () => {
+    const PAUSE_SWAP = 1;
+    const guardian = Keypair.generate();
+    const swap = () =>
+      program.methods
+        .swap(new BN(10 * 10 ** decimals), true, new BN(1), deadline())
+        .accountsStrict(poolAccounts())
+        .signers([user])
+        .rpc();
+
+    await expectError(
+      program.methods.setGuardian(guardian.publicKey).accountsStrict({ authority: user.publicKey, config }).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+    await program.methods.setGuardian(guardian.publicKey).accountsStrict({ authority: authority.publicKey, config }).signers([authority]).rpc();
+
+    await expectError(
+      program.methods.pause(PAUSE_SWAP).accountsStrict({ signer: user.publicKey, config }).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+    await program.methods.pause(PAUSE_SWAP).accountsStrict({ signer: guardian.publicKey, config }).signers([guardian]).rpc();
+    expect((await program.account.config.fetch(config)).paused).to.equal(PAUSE_SWAP);
+    await expectError(swap(), "PoolLocked");
+
+    await expectError(
+      program.methods.unpause(PAUSE_SWAP).accountsStrict({ authority: guardian.publicKey, config }).signers([guardian]).rpc(),
+      "InvalidAuthority"
+    );
+    await expectError(
+      program.methods.updateFee(25).accountsStrict({ authority: guardian.publicKey, config }).signers([guardian]).rpc(),
+      "InvalidAuthority"
+    );
+
+    await program.methods.unpause(PAUSE_SWAP).accountsStrict({ authority: authority.publicKey, config }).signers([authority]).rpc();
+    expect((await program.account.config.fetch(config)).paused).to.equal(0);
+    await swap();
+  });
+
+  it("skims and syncs a direct donation to the vault", async () => {
+    const donation = 50 * 10 ** decimals;
+    const donate = () =>
+      transferChecked(connection, user, userA, mintA, vaultA, user, donation, decimals, [], undefined, TOKEN_2022_PROGRAM_ID);
+    const recipient = Keypair.generate();
+    const recipientA = (await getOrCreateAssociatedTokenAccount(connection, authority, mintA, recipient.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    const recipientB = (await getOrCreateAssociatedTokenAccount(connection, authority, mintB, recipient.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID)).address;
+    const vaultAccounts = {
+      config,
+      lpToken,
+      mintA,
+      mintB,
+      vaultA,
+      vaultB,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
+    };
+    const skimAccounts = (signer: PublicKey) => ({
+      ...vaultAccounts,
+      authority: signer,
+      recipient: recipient.publicKey,
+      recipientA,
+      recipientB,
+    });
+
+    // Donations are not counted as reserves
+    const pool = await program.account.config.fetch(config);
+    await donate();
+    expect(await balance(vaultA)).to.equal(pool.reserveA.add(pool.protocolFeesA).toNumber() + donation);
+
+    await expectError(
+      program.methods.skim().accountsStrict(skimAccounts(user.publicKey)).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+    await program.methods.skim().accountsStrict(skimAccounts(authority.publicKey)).signers([authority]).rpc();
+    expect(await balance(recipientA)).to.equal(donation);
+    expect(await balance(recipientB)).to.equal(0);
+    expect(await balance(vaultA)).to.equal(pool.reserveA.add(pool.protocolFeesA).toNumber());
+
+    // Sync is permissionless but only against the pool's own mints
+    await donate();
+    await expectError(
+      program.methods.sync().accountsStrict({ ...vaultAccounts, mintA: mintB, mintB: mintA }).rpc(),
+      "InvalidToken"
+    );
+    await program.methods.sync().accountsStrict(vaultAccounts).rpc();
+    const synced = await program.account.config.fetch(config);
+    expect(synced.reserveA.toString()).to.equal(pool.reserveA.addn(donation).toString());
+    expect(synced.reserveB.toString()).to.equal(pool.reserveB.toString());
+  });
+
+  it("refuses to sync a pool before its first deposit", async () => {
+    const emptySeed = seed.addn(2);
+    const [emptyConfig] = PublicKey.findProgramAddressSync([Buffer.from("config"), emptySeed.toArrayLike(Buffer, "le", 8)], program.programId);
+    const [emptyLpToken] = PublicKey.findProgramAddressSync([Buffer.from("lp"), emptyConfig.toBuffer()], program.programId);
+    const [emptyLockedLiquidity] = PublicKey.findProgramAddressSync([Buffer.from("locked_liquidity"), emptyConfig.toBuffer()], program.programId);
+    const emptyVaultA = getAssociatedTokenAddressSync(mintA, emptyConfig, true, TOKEN_2022_PROGRAM_ID);
+    const emptyVaultB = getAssociatedTokenAddressSync(mintB, emptyConfig, true, TOKEN_2022_PROGRAM_ID);
+    await program.methods.initialize(emptySeed, feeBps, authority.publicKey, 0).accountsStrict({
+      signer: authority.publicKey,
+      mintA,
+      mintB,
+      lpToken: emptyLpToken,
+      vaultA: emptyVaultA,
+      vaultB: emptyVaultB,
+      config: emptyConfig,
+      hookRegistry,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgramA: TOKEN_2022_PROGRAM_ID,
+      tokenProgramB: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    }).signers([authority]).rpc();
+
+    // One base unit donated before any LP exists must not be turned into reserves
+    await transferChecked(connection, user, userA, mintA, emptyVaultA, user, 1, decimals, [], undefined, TOKEN_2022_PROGRAM_ID);
+    await expectError(
+      program.methods.sync().accountsStrict({
+        config: emptyConfig,
+        lpToken: emptyLpToken,
+        mintA,
+        mintB,
+        vaultA: emptyVaultA,
+        vaultB: emptyVaultB,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_2022_PROGRAM_ID,
+      }).rpc(),
+      "NoLiquidityInPool"
+    );
+
+    // The pool still takes its first deposit; the donation stays outside the reserves
+    const seedAmount = new BN(1_000 * 10 ** decimals);
+    await program.methods
+      .deposit(new BN(1), seedAmount, seedAmount, deadline())
+      .accountsStrict({
+        ...poolAccounts(),
+        lpToken: emptyLpToken,
+        vaultA: emptyVaultA,
+        vaultB: emptyVaultB,
+        config: emptyConfig,
+        userLp: getAssociatedTokenAddressSync(emptyLpToken, user.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        lockedLiquidity: emptyLockedLiquidity,
+        lockedLp: getAssociatedTokenAddressSync(emptyLpToken, emptyLockedLiquidity, true, TOKEN_2022_PROGRAM_ID),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+    const pool = await program.account.config.fetch(emptyConfig);
+    expect(pool.reserveA.toString()).to.equal(seedAmount.toString());
+    expect(await balance(emptyVaultA)).to.equal(seedAmount.toNumber() + 1);
+  });
+
+  it("hands the hook registry admin over in two steps", async () => {
+    const newAdmin = Keypair.generate();
+    const accept = (signer: Keypair) =>
+      program.methods.acceptHookRegistryAdmin().accountsStrict({ pendingAdmin: signer.publicKey, hookRegistry }).signers([signer]).rpc();
+
+    await expectError(
+      program.methods.proposeHookRegistryAdmin(user.publicKey).accountsStrict({ admin: user.publicKey, hookRegistry }).signers([user]).rpc(),
+      "InvalidAuthority"
+    );
+    await program.methods.proposeHookRegistryAdmin(newAdmin.publicKey).accountsStrict({ admin: provider.publicKey, hookRegistry }).rpc();
+    await expectError(accept(user), "InvalidPendingAuthority");
+    await accept(newAdmin);
+    const registry = await program.account.hookRegistry.fetch(hookRegistry);
+    expect(registry.admin.toBase58()).to.equal(newAdmin.publicKey.toBase58());
+    expect(registry.pendingAdmin).to.be.null;
+
+    // Hand it back to the provider wallet, which the other test files expect
+    await program.methods.proposeHookRegistryAdmin(provider.publicKey).accountsStrict({ admin: newAdmin.publicKey, hookRegistry }).signers([newAdmin]).rpc();
+    await program.methods.acceptHookRegistryAdmin().accountsStrict({ pendingAdmin: provider.publicKey, hookRegistry }).rpc();
+  });
+});
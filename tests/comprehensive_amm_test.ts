@@ -14,6 +14,7 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
   NATIVE_MINT_2022,
   createInitializeMintInstruction,
   createMintToInstruction,
@@ -24,10 +25,15 @@ import {
   ExtensionType,
   getOrCreateAssociatedTokenAccount,
   getAccount,
-  createAssociatedTokenAccountInstruction
+  createAssociatedTokenAccountInstruction,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createCloseAccountInstruction,
+  createSyncNativeInstruction,
+  createTransferCheckedWithTransferHookInstruction
 } from "@solana/spl-token";
 import { BN } from "bn.js";
 import { expect } from "chai";
+import { ensureHookApproved, hookRegistryAddress } from "./helpers";
 
 describe("Comprehensive AMM + Transfer Hook Test", () => {
   const provider = anchor.AnchorProvider.env();
@@ -43,13 +49,15 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
 
   let config: PublicKey;
   let lpToken: PublicKey;
-  let vault: PublicKey;
-  let solVault: PublicKey;
-  let wsolVault: PublicKey;
+  // Side A is the hooked Token-2022 mint, side B is legacy wrapped SOL
+  let vaultA: PublicKey;
+  let vaultB: PublicKey;
   let extraAccountMetaList: PublicKey;
+  let hookConfig: PublicKey;
 
   let userTokenAccount: PublicKey;
   let userWsolAccount: PublicKey;
+  let userPoolWsolAccount: PublicKey;
   let userLpAccount: PublicKey;
 
   let delegatePda: PublicKey;
@@ -59,7 +67,87 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
   const seed = new BN(Date.now());
   const feeBps = 300;
   const initialTokenSupply = new BN(1000 * 10 ** decimals);
-  const initialSolAmount = new BN(10 * LAMPORTS_PER_SOL);
+  // Deadline one minute ahead of the local clock for swap/deposit/withdraw
+  const deadline = () => new BN(Math.floor(Date.now() / 1000) + 60);
+  // Side B is wrapped SOL: the pool wraps the user's lamports into a temporary WSOL account
+  // and closes it after each call, so the user pays and receives native SOL
+  const userSolBalance = () => connection.getBalance(user.publicKey, "confirmed");
+  const openWsolAccount = () =>
+    createAssociatedTokenAccountIdempotentInstruction(
+      user.publicKey,
+      userPoolWsolAccount,
+      user.publicKey,
+      NATIVE_MINT,
+      TOKEN_PROGRAM_ID
+    );
+
+  const walletRecord = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("wallet-record"), tokenMint.publicKey.toBuffer(), owner.toBuffer()],
+      transferHookProgram.programId
+    )[0];
+  const transferStats = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("transfer-stats"), tokenMint.publicKey.toBuffer(), owner.toBuffer()],
+      transferHookProgram.programId
+    )[0];
+  const blockedWallet = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("blocked-wallet"), tokenMint.publicKey.toBuffer(), owner.toBuffer()],
+      transferHookProgram.programId
+    )[0];
+  // Everything the hook resolves from the ExtraAccountMetaList for tokenMint transfers between
+  // the user and the pool, in either direction; each transfer picks out its own accounts by key
+  const hookAccounts = () => [
+    { pubkey: extraAccountMetaList, isWritable: false, isSigner: false },
+    { pubkey: transferHookProgram.programId, isWritable: false, isSigner: false },
+    { pubkey: NATIVE_MINT_2022, isWritable: false, isSigner: false },
+    { pubkey: TOKEN_2022_PROGRAM_ID, isWritable: false, isSigner: false },
+    { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+    { pubkey: delegatePda, isWritable: true, isSigner: false },
+    { pubkey: delegateWsolAta, isWritable: true, isSigner: false },
+    { pubkey: userWsolAccount, isWritable: true, isSigner: false },
+    { pubkey: getAssociatedTokenAddressSync(NATIVE_MINT_2022, config, true, TOKEN_2022_PROGRAM_ID), isWritable: true, isSigner: false },
+    { pubkey: hookConfig, isWritable: false, isSigner: false },
+    { pubkey: walletRecord(user.publicKey), isWritable: false, isSigner: false },
+    { pubkey: walletRecord(config), isWritable: false, isSigner: false },
+    { pubkey: blockedWallet(user.publicKey), isWritable: false, isSigner: false },
+    { pubkey: blockedWallet(config), isWritable: false, isSigner: false },
+    { pubkey: transferStats(user.publicKey), isWritable: true, isSigner: false },
+    { pubkey: transferStats(config), isWritable: true, isSigner: false },
+  ];
+
+  // Plain user -> payer transfers of tokenMint used by the hook rule tests; test 11 creates
+  // the payer's account
+  const oneToken = 10 ** decimals;
+  const payerTokenAccount = () =>
+    getAssociatedTokenAddressSync(tokenMint.publicKey, payer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  const transferToPayer = async (amount = oneToken) => sendAndConfirmTransaction(connection, new Transaction().add(
+    await createTransferCheckedWithTransferHookInstruction(
+      connection,
+      userTokenAccount,
+      tokenMint.publicKey,
+      payerTokenAccount(),
+      user.publicKey,
+      BigInt(amount),
+      decimals,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    )
+  ), [user]);
+  // Hook errors surface in the simulation logs of the failed transaction
+  const expectRejected = async (code: string, call: () => Promise<unknown> = () => transferToPayer()) => {
+    try {
+      await call();
+      expect.fail(`should fail with ${code}`);
+    } catch (err) {
+      expect((err.logs ?? []).join("\n")).to.contain(code);
+    }
+  };
+
+  // Pools for hooked mints need the transfer-hook program in the global registry
+  const hookRegistry = hookRegistryAddress(program);
 
   before(async () => {
     payer = Keypair.generate();
@@ -72,11 +160,12 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
     ]);
 
     await new Promise(resolve => setTimeout(resolve, 2000));
+    await ensureHookApproved(program, transferHookProgram.programId);
   });
 
   it("1. Create Token-2022 with Transfer Hook", async () => {
     [delegatePda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("delegate")],
+      [Buffer.from("delegate"), tokenMint.publicKey.toBuffer()],
       transferHookProgram.programId
     );
 
@@ -157,6 +246,10 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       [Buffer.from("extra-account-metas"), tokenMint.publicKey.toBuffer()],
       transferHookProgram.programId
     );
+    [hookConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hook-config"), tokenMint.publicKey.toBuffer()],
+      transferHookProgram.programId
+    );
 
     await transferHookProgram.methods
       .initializeExtraAccountMetaList()
@@ -165,6 +258,7 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
         extraAccountMetaList,
         mint: tokenMint.publicKey,
         wsolMint: NATIVE_MINT_2022,
+        hookConfig,
         delegatePda,
         delegateWsolAta,
         systemProgram: SystemProgram.programId,
@@ -173,6 +267,130 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       })
       .signers([payer])
       .rpc();
+
+    // Defaults to the original flat 0.1% with no floor or cap
+    const feeConfig = await transferHookProgram.account.hookConfig.fetch(hookConfig);
+    expect(feeConfig.admin.toBase58()).to.equal(payer.publicKey.toBase58());
+    expect(feeConfig.feeBps).to.equal(10);
+    expect(feeConfig.minFee.toString()).to.equal("0");
+  });
+
+  it("2a. Update Transfer Hook Fee Config", async () => {
+    try {
+      await transferHookProgram.methods
+        .updateHookConfig(10, new BN(5), new BN(1))
+        .accountsStrict({ admin: payer.publicKey, hookConfig })
+        .signers([payer])
+        .rpc();
+      expect.fail("min fee above max fee should be rejected");
+    } catch (err) {
+      expect(err.error?.errorCode?.code).to.equal("InvalidFeeBounds");
+    }
+
+    // Keep the 0.1% rate the fee assertions below rely on, but add a cap
+    const maxFee = new BN(LAMPORTS_PER_SOL);
+    await transferHookProgram.methods
+      .updateHookConfig(10, new BN(0), maxFee)
+      .accountsStrict({ admin: payer.publicKey, hookConfig })
+      .signers([payer])
+      .rpc();
+    const feeConfig = await transferHookProgram.account.hookConfig.fetch(hookConfig);
+    expect(feeConfig.maxFee.toString()).to.equal(maxFee.toString());
+  });
+
+  it("2b. Two-Step Hook Admin Handover", async () => {
+    const newAdmin = Keypair.generate();
+    await transferHookProgram.methods
+      .proposeHookAdmin(newAdmin.publicKey)
+      .accountsStrict({ admin: payer.publicKey, hookConfig })
+      .signers([payer])
+      .rpc();
+
+    // Only the proposed key can accept
+    try {
+      await transferHookProgram.methods
+        .acceptHookAdmin()
+        .accountsStrict({ pendingAdmin: user.publicKey, hookConfig })
+        .signers([user])
+        .rpc();
+      expect.fail("accept by a non-pending key should be rejected");
+    } catch (err) {
+      expect(err.error?.errorCode?.code).to.equal("InvalidPendingAdmin");
+    }
+
+    await transferHookProgram.methods
+      .acceptHookAdmin()
+      .accountsStrict({ pendingAdmin: newAdmin.publicKey, hookConfig })
+      .signers([newAdmin])
+      .rpc();
+    let config = await transferHookProgram.account.hookConfig.fetch(hookConfig);
+    expect(config.admin.toBase58()).to.equal(newAdmin.publicKey.toBase58());
+    expect(config.pendingAdmin).to.equal(null);
+
+    // Hand it back so the later admin calls keep signing with payer
+    await transferHookProgram.methods
+      .proposeHookAdmin(payer.publicKey)
+      .accountsStrict({ admin: newAdmin.publicKey, hookConfig })
+      .signers([newAdmin])
+      .rpc();
+    await transferHookProgram.methods
+      .acceptHookAdmin()
+      .accountsStrict({ pendingAdmin: payer.publicKey, hookConfig })
+      .signers([payer])
+      .rpc();
+    config = await transferHookProgram.account.hookConfig.fetch(hookConfig);
+    expect(config.admin.toBase58()).to.equal(payer.publicKey.toBase58());
+  });
+
+  it("2c. Refresh ExtraAccountMetaList", async () => {
+    const refreshAccounts = (admin: PublicKey) => ({
+      admin,
+      extraAccountMetaList,
+      hookConfig,
+      wsolMint: NATIVE_MINT_2022,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    try {
+      await transferHookProgram.methods
+        .updateExtraAccountMetaList()
+        .accountsStrict(refreshAccounts(user.publicKey))
+        .signers([user])
+        .rpc();
+      expect.fail("non-admin refresh should be rejected");
+    } catch (err) {
+      expect(err.error?.errorCode?.code).to.equal("Unauthorized");
+    }
+
+    // Rewriting an up-to-date list leaves its size unchanged
+    const before = await connection.getAccountInfo(extraAccountMetaList);
+    await transferHookProgram.methods
+      .updateExtraAccountMetaList()
+      .accountsStrict(refreshAccounts(payer.publicKey))
+      .signers([payer])
+      .rpc();
+    const after = await connection.getAccountInfo(extraAccountMetaList);
+    expect(after.data.length).to.equal(before.data.length);
+    expect(after.data.equals(before.data)).to.equal(true);
+
+    // HookConfig already exists for this mint
+    try {
+      await transferHookProgram.methods
+        .initHookConfig()
+        .accountsStrict({
+          payer: payer.publicKey,
+          mint: tokenMint.publicKey,
+          hookConfig,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc();
+      expect.fail("init_hook_config should not overwrite an existing config");
+    } catch (err) {
+      expect(err).to.be.an("error");
+    }
   });
 
   it("3. Prepare Transfer Hook Fee System", async () => {
@@ -213,6 +431,9 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
     );
 
     await sendAndConfirmTransaction(connection, approveTx, [user]);
+
+    // Temporary WSOL account for the pool's B side; opened before each pool call
+    userPoolWsolAccount = getAssociatedTokenAddressSync(NATIVE_MINT, user.publicKey, false, TOKEN_PROGRAM_ID);
   });
 
   it("4. Initialize AMM Pool", async () => {
@@ -226,39 +447,35 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       program.programId
     );
 
-    [solVault] = PublicKey.findProgramAddressSync(
-      [Buffer.from("sol_vault"), config.toBuffer()],
-      program.programId
-    );
-
-    vault = getAssociatedTokenAddressSync(
+    vaultA = getAssociatedTokenAddressSync(
       tokenMint.publicKey,
       config,
       true,
       TOKEN_2022_PROGRAM_ID
     );
 
-    wsolVault = getAssociatedTokenAddressSync(
-      NATIVE_MINT_2022,
+    vaultB = getAssociatedTokenAddressSync(
+      NATIVE_MINT,
       config,
       true,
-      TOKEN_2022_PROGRAM_ID
+      TOKEN_PROGRAM_ID
     );
 
     await program.methods
-      .initialize(seed, feeBps, payer.publicKey)
+      .initialize(seed, feeBps, payer.publicKey, 0)
       .accountsStrict({
         signer: payer.publicKey,
-        extraAccountMetaList,
-        mint: tokenMint.publicKey,
-        wsolMint: NATIVE_MINT_2022,
+        mintA: tokenMint.publicKey,
+        mintB: NATIVE_MINT,
         lpToken,
-        vault,
-        solVault,
-        wsolVault,
+        vaultA,
+        vaultB,
         config,
+        hookRegistry,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
       .signers([payer])
@@ -268,11 +485,66 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
     expect(poolConfig.seed.toString()).to.equal(seed.toString());
     expect(poolConfig.fee).to.equal(feeBps);
     expect(poolConfig.authority.toBase58()).to.equal(payer.publicKey.toBase58());
+    expect(poolConfig.mintA.toBase58()).to.equal(tokenMint.publicKey.toBase58());
+    expect(poolConfig.mintB.toBase58()).to.equal(NATIVE_MINT.toBase58());
+    expect(poolConfig.tokenProgramB.toBase58()).to.equal(TOKEN_PROGRAM_ID.toBase58());
+    // Hooked by this repo's transfer-hook program (EXT_TRANSFER_HOOK); legacy WSOL has no extensions
+    expect(poolConfig.extensionsA).to.equal(1 << 1);
+    expect(poolConfig.extensionsB).to.equal(0);
+  });
+
+  it("4a. Reject Pool for Mint with Unapproved Hook Program", async () => {
+    // Same layout as the pool mint, but hooked by the AMM program itself, which is not in the registry
+    const rogueMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: payer.publicKey,
+        newAccountPubkey: rogueMint.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeTransferHookInstruction(rogueMint.publicKey, payer.publicKey, program.programId, TOKEN_2022_PROGRAM_ID),
+      createInitializeMintInstruction(rogueMint.publicKey, decimals, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    ), [payer, rogueMint]);
+
+    const rogueSeed = seed.addn(1);
+    const [rogueConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), rogueSeed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [rogueLp] = PublicKey.findProgramAddressSync([Buffer.from("lp"), rogueConfig.toBuffer()], program.programId);
+
+    try {
+      await program.methods
+        .initialize(rogueSeed, feeBps, payer.publicKey, 0)
+        .accountsStrict({
+          signer: payer.publicKey,
+          mintA: rogueMint.publicKey,
+          mintB: NATIVE_MINT,
+          lpToken: rogueLp,
+          vaultA: getAssociatedTokenAddressSync(rogueMint.publicKey, rogueConfig, true, TOKEN_2022_PROGRAM_ID),
+          vaultB: getAssociatedTokenAddressSync(NATIVE_MINT, rogueConfig, true, TOKEN_PROGRAM_ID),
+          config: rogueConfig,
+          hookRegistry,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenProgramA: TOKEN_2022_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([payer])
+        .rpc();
+      expect.fail("pool with an unapproved hook program should not initialize");
+    } catch (err) {
+      expect(err.error?.errorCode?.code).to.equal("HookProgramNotApproved");
+    }
   });
 
   it("5. Deposit Liquidity to Pool", async () => {
-    const tokenAmount = new BN(100 * 10 ** decimals);
-    const solAmount = new BN(5 * LAMPORTS_PER_SOL);
+    // First deposit: LP out is sqrt(token * sol) minus the locked minimum; this is the floor we accept
+    const minLp = new BN(1);
     const maxToken = new BN(110 * 10 ** decimals);
     const maxSol = new BN(5.5 * LAMPORTS_PER_SOL);
 
@@ -283,38 +555,43 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       TOKEN_PROGRAM_ID
     );
 
+    const [lockedLiquidity] = PublicKey.findProgramAddressSync(
+      [Buffer.from("locked_liquidity"), config.toBuffer()],
+      program.programId
+    );
+    const lockedLp = getAssociatedTokenAddressSync(lpToken, lockedLiquidity, true, TOKEN_2022_PROGRAM_ID);
+
     const initialTokenBalance = await getAccount(
       connection,
       userTokenAccount,
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const initialSolBalance = await connection.getBalance(user.publicKey);
+    const initialSolBalance = await userSolBalance();
 
     await program.methods
-      .deposit(solAmount, tokenAmount, maxSol, maxToken)
+      .deposit(minLp, maxToken, maxSol, deadline())
       .accountsStrict({
         signer: user.publicKey,
-        mintx: tokenMint.publicKey,
-        minty: NATIVE_MINT_2022,
-        userX: userTokenAccount,
-        userY: userWsolAccount,
+        mintA: tokenMint.publicKey,
+        mintB: NATIVE_MINT,
+        userA: userTokenAccount,
+        userB: userPoolWsolAccount,
         userLp: userLpAccount,
         lpToken,
-        vaultX: vault,
-        vaultY: wsolVault,
+        lockedLiquidity,
+        lockedLp,
+        vaultA,
+        vaultB,
         config,
-        solVault,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
-      .remainingAccounts([
-        { pubkey: extraAccountMetaList, isWritable: false, isSigner: false },
-        { pubkey: transferHookProgram.programId, isWritable: false, isSigner: false },
-        { pubkey: delegatePda, isWritable: false, isSigner: false },
-        { pubkey: delegateWsolAta, isWritable: true, isSigner: false },
-      ])
+      .remainingAccounts(hookAccounts())
+      .preInstructions([openWsolAccount()])
       .signers([user])
       .rpc();
 
@@ -326,16 +603,27 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
     );
     expect(Number(userLpBalance.amount)).to.be.greaterThan(0);
 
+    // The minimum liquidity is minted to the dead PDA and can never be withdrawn
+    const lockedLpBalance = await getAccount(connection, lockedLp, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(Number(lockedLpBalance.amount)).to.equal(1000);
+
     const vaultTokenBalance = await getAccount(
       connection,
-      vault,
+      vaultA,
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const vaultSolBalance = await connection.getBalance(solVault);
+    const vaultWsolBalance = await getAccount(connection, vaultB, "confirmed", TOKEN_PROGRAM_ID);
 
     expect(Number(vaultTokenBalance.amount)).to.be.greaterThan(0);
-    expect(vaultSolBalance).to.be.greaterThan(0);
+    expect(Number(vaultWsolBalance.amount)).to.be.greaterThan(0);
+
+    // Stored reserves track the vault balances each side was deposited into
+    const poolConfig = await program.account.config.fetch(config);
+    expect(poolConfig.reserveA.toString()).to.equal(vaultTokenBalance.amount.toString());
+    expect(poolConfig.reserveB.toString()).to.equal(vaultWsolBalance.amount.toString());
+    // Side B was paid in native SOL, wrapped inside the instruction
+    expect(initialSolBalance - (await userSolBalance())).to.be.at.least(Number(vaultWsolBalance.amount));
   });
 
   it("6. Perform Token-to-SOL Swap", async () => {
@@ -348,32 +636,26 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const initialSolBalance = await connection.getBalance(user.publicKey);
+    const initialSolBalance = await userSolBalance();
 
     await program.methods
-      .swap(swapAmount, true, minOut)
+      .swap(swapAmount, true, minOut, deadline())
       .accountsStrict({
         signer: user.publicKey,
-        mintx: tokenMint.publicKey,
-        minty: NATIVE_MINT_2022,
-        userX: userTokenAccount,
-        userY: userWsolAccount,
-        userLp: userLpAccount,
+        mintA: tokenMint.publicKey,
+        mintB: NATIVE_MINT,
+        userA: userTokenAccount,
+        userB: userPoolWsolAccount,
         lpToken,
-        vaultX: vault,
-        vaultY: wsolVault,
+        vaultA,
+        vaultB,
         config,
-        solVault,
         systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
       })
-      .remainingAccounts([
-        { pubkey: extraAccountMetaList, isWritable: false, isSigner: false },
-        { pubkey: transferHookProgram.programId, isWritable: false, isSigner: false },
-        { pubkey: delegatePda, isWritable: false, isSigner: false },
-        { pubkey: delegateWsolAta, isWritable: true, isSigner: false },
-      ])
+      .remainingAccounts(hookAccounts())
+      .preInstructions([openWsolAccount()])
       .signers([user])
       .rpc();
 
@@ -385,9 +667,12 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const finalSolBalance = await connection.getBalance(user.publicKey);
+    const finalSolBalance = await userSolBalance();
 
     expect(Number(finalTokenBalance.amount)).to.be.lessThan(Number(initialTokenBalance.amount));
+    // SOL paid out of the reserve that deposit funded, unwrapped back to the user
+    expect(finalSolBalance).to.be.greaterThan(initialSolBalance);
+    expect(await connection.getAccountInfo(userPoolWsolAccount)).to.be.null;
     
     const delegateFeeBalance = await getAccount(
       connection,
@@ -408,32 +693,26 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const initialSolBalance = await connection.getBalance(user.publicKey);
+    const initialSolBalance = await userSolBalance();
 
     await program.methods
-      .swap(swapAmount, false, minOut)
+      .swap(swapAmount, false, minOut, deadline())
       .accountsStrict({
         signer: user.publicKey,
-        mintx: tokenMint.publicKey,
-        minty: NATIVE_MINT_2022,
-        userX: userTokenAccount,
-        userY: userWsolAccount,
-        userLp: userLpAccount,
+        mintA: tokenMint.publicKey,
+        mintB: NATIVE_MINT,
+        userA: userTokenAccount,
+        userB: userPoolWsolAccount,
         lpToken,
-        vaultX: vault,
-        vaultY: wsolVault,
+        vaultA,
+        vaultB,
         config,
-        solVault,
         systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_2022_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
       })
-      .remainingAccounts([
-        { pubkey: extraAccountMetaList, isWritable: false, isSigner: false },
-        { pubkey: transferHookProgram.programId, isWritable: false, isSigner: false },
-        { pubkey: delegatePda, isWritable: false, isSigner: false },
-        { pubkey: delegateWsolAta, isWritable: true, isSigner: false },
-      ])
+      .remainingAccounts(hookAccounts())
+      .preInstructions([openWsolAccount()])
       .signers([user])
       .rpc();
 
@@ -446,15 +725,54 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const finalSolBalance = await connection.getBalance(user.publicKey);
+    const finalSolBalance = await userSolBalance();
 
     console.log(`Post-swap token balance: ${finalTokenBalance.amount}`);
-    console.log(`Post-swap SOL balance: ${finalSolBalance / LAMPORTS_PER_SOL} SOL`);
+    console.log(`Post-swap WSOL balance: ${finalSolBalance / LAMPORTS_PER_SOL} SOL`);
 
     // Verify tokens were received
     expect(Number(finalTokenBalance.amount)).to.be.greaterThan(Number(initialTokenBalance.amount));
   });
 
+  it("7a. Reject a WSOL Account That Already Holds SOL", async () => {
+    // The pool closes the native side's account after the call, so one holding WSOL from
+    // elsewhere is refused rather than swept into the signer's lamports
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      openWsolAccount(),
+      SystemProgram.transfer({ fromPubkey: user.publicKey, toPubkey: userPoolWsolAccount, lamports: 0.1 * LAMPORTS_PER_SOL }),
+      createSyncNativeInstruction(userPoolWsolAccount, TOKEN_PROGRAM_ID)
+    ), [user]);
+
+    try {
+      await program.methods
+        .swap(new BN(0.1 * LAMPORTS_PER_SOL), false, new BN(0), deadline())
+        .accountsStrict({
+          signer: user.publicKey,
+          mintA: tokenMint.publicKey,
+          mintB: NATIVE_MINT,
+          userA: userTokenAccount,
+          userB: userPoolWsolAccount,
+          lpToken,
+          vaultA,
+          vaultB,
+          config,
+          systemProgram: SystemProgram.programId,
+          tokenProgramA: TOKEN_2022_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(hookAccounts())
+        .signers([user])
+        .rpc();
+      expect.fail("swap with a funded WSOL account should be rejected");
+    } catch (err) {
+      expect(err.error?.errorCode?.code).to.equal("NativeAccountNotEmpty");
+    }
+
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      createCloseAccountInstruction(userPoolWsolAccount, user.publicKey, user.publicKey, [], TOKEN_PROGRAM_ID)
+    ), [user]);
+  });
+
   it("8. Withdraw Liquidity from Pool", async () => {
     console.log("\n💸 Withdrawing liquidity from pool...");
 
@@ -480,33 +798,30 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const initialSolBalance = await connection.getBalance(user.publicKey);
+    const initialSolBalance = await userSolBalance();
 
     // Withdraw liquidity
     await program.methods
-      .withdraw(withdrawAmount, minToken, minSol)
+      .withdraw(withdrawAmount, minToken, minSol, deadline())
       .accountsStrict({
         signer: user.publicKey,
-        mintx: tokenMint.publicKey,
-        minty: NATIVE_MINT_2022,
-        userX: userTokenAccount,
-        userY: userWsolAccount,
+        mintA: tokenMint.publicKey,
+        mintB: NATIVE_MINT,
+        userA: userTokenAccount,
+        userB: userPoolWsolAccount,
         userLp: userLpAccount,
         lpToken,
-        vaultX: vault,
-        vaultY: wsolVault,
+        vaultA,
+        vaultB,
         config,
-        solVault,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgramA: TOKEN_2022_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
-      .remainingAccounts([
-        { pubkey: extraAccountMetaList, isWritable: false, isSigner: false },
-        { pubkey: transferHookProgram.programId, isWritable: false, isSigner: false },
-        { pubkey: delegatePda, isWritable: false, isSigner: false },
-        { pubkey: delegateWsolAta, isWritable: true, isSigner: false },
-      ])
+      .remainingAccounts(hookAccounts())
+      .preInstructions([openWsolAccount()])
       .signers([user])
       .rpc();
 
@@ -528,12 +843,18 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
       "confirmed",
       TOKEN_2022_PROGRAM_ID
     );
-    const finalSolBalance = await connection.getBalance(user.publicKey);
+    const finalSolBalance = await userSolBalance();
 
     console.log(`Final token balance: ${finalTokenBalance.amount}`);
-    console.log(`Final SOL balance: ${finalSolBalance / LAMPORTS_PER_SOL} SOL`);
+    console.log(`Final WSOL balance: ${finalSolBalance / LAMPORTS_PER_SOL} SOL`);
 
     expect(Number(finalTokenBalance.amount)).to.be.greaterThan(Number(initialTokenBalance.amount));
+    // SOL swapped in earlier is paid back out to LPs
+    expect(finalSolBalance).to.be.greaterThan(initialSolBalance);
+
+    const poolConfig = await program.account.config.fetch(config);
+    const vaultWsolBalance = await getAccount(connection, vaultB, "confirmed", TOKEN_PROGRAM_ID);
+    expect(poolConfig.reserveB.add(poolConfig.protocolFeesB).toString()).to.equal(vaultWsolBalance.amount.toString());
   });
 
   it("9. Verify Transfer Hook Fee Collection", async () => {
@@ -558,5 +879,293 @@ describe("Comprehensive AMM + Transfer Hook Test", () => {
     expect(Number(delegateFeeBalance.amount)).to.be.greaterThan(expectedMinimumFees);
   });
 
+  it("10. Withdraw Collected Hook Fees", async () => {
+    const treasuryWsol = getAssociatedTokenAddressSync(NATIVE_MINT_2022, payer.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      createAssociatedTokenAccountInstruction(
+        payer.publicKey,
+        treasuryWsol,
+        payer.publicKey,
+        NATIVE_MINT_2022,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      )
+    ), [payer]);
+
+    const collected = (await getAccount(connection, delegateWsolAta, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+    const accounts = {
+      hookConfig,
+      mint: tokenMint.publicKey,
+      wsolMint: NATIVE_MINT_2022,
+      delegate: delegatePda,
+      delegateWsolTokenAccount: delegateWsolAta,
+      destination: treasuryWsol,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+
+    // Only the mint's hook admin can move fees out
+    try {
+      await transferHookProgram.methods
+        .withdrawFees(new BN(collected.toString()))
+        .accountsStrict({ admin: user.publicKey, ...accounts })
+        .signers([user])
+        .rpc();
+      expect.fail("non-admin fee withdrawal should be rejected");
+    } catch (err) {
+      expect(err.error?.errorCode?.code).to.equal("Unauthorized");
+    }
+
+    await transferHookProgram.methods
+      .withdrawFees(new BN(collected.toString()))
+      .accountsStrict({ admin: payer.publicKey, ...accounts })
+      .signers([payer])
+      .rpc();
+
+    const treasuryBalance = await getAccount(connection, treasuryWsol, "confirmed", TOKEN_2022_PROGRAM_ID);
+    const delegateBalance = await getAccount(connection, delegateWsolAta, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(treasuryBalance.amount.toString()).to.equal(collected.toString());
+    expect(Number(delegateBalance.amount)).to.equal(0);
+  });
+
+  it("11. Allowlist Mode Gates Transfers", async () => {
+    await sendAndConfirmTransaction(connection, new Transaction().add(
+      createAssociatedTokenAccountInstruction(
+        payer.publicKey,
+        payerTokenAccount(),
+        payer.publicKey,
+        tokenMint.publicKey,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      )
+    ), [payer]);
+
+    const walletAccounts = (owner: PublicKey) => ({
+      admin: payer.publicKey,
+      hookConfig,
+      walletRecord: walletRecord(owner),
+    });
+
+    await transferHookProgram.methods
+      .setAllowlistMode(true)
+      .accountsStrict({ admin: payer.publicKey, hookConfig })
+      .signers([payer])
+      .rpc();
+    await expectRejected("WalletNotApproved");
+
+    for (const owner of [user.publicKey, payer.publicKey]) {
+      await transferHookProgram.methods
+        .addWallet(owner, new BN(0))
+        .accountsStrict({ ...walletAccounts(owner), systemProgram: SystemProgram.programId })
+        .signers([payer])
+        .rpc();
+    }
+    await transferToPayer();
+    const payerBalance = await getAccount(connection, payerTokenAccount(), "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(payerBalance.amount.toString()).to.equal(oneToken.toString());
+
+    // A past expiry keeps the record but stops it from authorizing transfers
+    await transferHookProgram.methods
+      .setWalletExpiry(payer.publicKey, new BN(1))
+      .accountsStrict(walletAccounts(payer.publicKey))
+      .signers([payer])
+      .rpc();
+    await expectRejected("WalletApprovalExpired");
+
+    await transferHookProgram.methods
+      .revokeWallet(user.publicKey)
+      .accountsStrict(walletAccounts(user.publicKey))
+      .signers([payer])
+      .rpc();
+    expect(await transferHookProgram.account.walletRecord.fetchNullable(walletRecord(user.publicKey))).to.be.null;
+
+    await transferHookProgram.methods
+      .setAllowlistMode(false)
+      .accountsStrict({ admin: payer.publicKey, hookConfig })
+      .signers([payer])
+      .rpc();
+  });
+
+  it("12. Blocklist Rejects Transfers from Blocked Owners", async () => {
+    // Bulk entry point; re-submitting an owner that is already blocked is a no-op
+    const sanctioned = [user.publicKey, Keypair.generate().publicKey];
+    // A few lamports sent to a record PDA ahead of time must not stop it being created
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.transfer({ fromPubkey: payer.publicKey, toPubkey: blockedWallet(sanctioned[1]), lamports: 1_000 })
+      ),
+      [payer]
+    );
+    for (let i = 0; i < 2; i++) {
+      await transferHookProgram.methods
+        .blockWallets(sanctioned)
+        .accountsStrict({ admin: payer.publicKey, hookConfig, systemProgram: SystemProgram.programId })
+        .remainingAccounts(sanctioned.map(owner => ({ pubkey: blockedWallet(owner), isWritable: true, isSigner: false })))
+        .signers([payer])
+        .rpc();
+    }
+    const prefunded = await transferHookProgram.account.blockedWallet.fetch(blockedWallet(sanctioned[1]));
+    expect(prefunded.owner.toBase58()).to.equal(sanctioned[1].toBase58());
+
+    await expectRejected("WalletBlocked");
+
+    await transferHookProgram.methods
+      .unblockWallet(user.publicKey)
+      .accountsStrict({ admin: payer.publicKey, hookConfig, blockedWallet: blockedWallet(user.publicKey) })
+      .signers([payer])
+      .rpc();
+    await transferToPayer();
+
+    // Blocking the destination owner works the same way
+    await transferHookProgram.methods
+      .blockWallet(payer.publicKey)
+      .accountsStrict({
+        admin: payer.publicKey,
+        hookConfig,
+        blockedWallet: blockedWallet(payer.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([payer])
+      .rpc();
+    await expectRejected("WalletBlocked");
+
+    await transferHookProgram.methods
+      .unblockWallet(payer.publicKey)
+      .accountsStrict({ admin: payer.publicKey, hookConfig, blockedWallet: blockedWallet(payer.publicKey) })
+      .signers([payer])
+      .rpc();
+  });
+
+  it("13. Velocity Limits Cap Per-Transfer and 24h Volume", async () => {
+    const setLimits = (perTransfer: number, daily: number) =>
+      transferHookProgram.methods
+        .setVelocityLimits(new BN(perTransfer), new BN(daily))
+        .accountsStrict({ admin: payer.publicKey, hookConfig })
+        .signers([payer])
+        .rpc();
+
+    await setLimits(2 * oneToken, 3 * oneToken);
+    await expectRejected("TransferAmountExceedsLimit", () => transferToPayer(3 * oneToken));
+    // The daily window lives in the sender's stats account, which must exist first
+    await expectRejected("TransferStatsMissing");
+
+    await transferHookProgram.methods
+      .initTransferStats(user.publicKey)
+      .accountsStrict({
+        payer: payer.publicKey,
+        hookConfig,
+        transferStats: transferStats(user.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([payer])
+      .rpc();
+
+    await transferToPayer(2 * oneToken);
+    await transferToPayer(oneToken);
+    await expectRejected("DailyLimitExceeded", () => transferToPayer(1));
+
+    const stats = await transferHookProgram.account.transferStats.fetch(transferStats(user.publicKey));
+    const sent = stats.buckets.reduce((total, bucket) => total.add(bucket), new BN(0));
+    expect(sent.toString()).to.equal((3 * oneToken).toString());
+
+    // Buying from the pool sends out of vault_a, owned by the pool config like every other swap
+    const buyTokens = () =>
+      program.methods
+        .swap(new BN(0.01 * LAMPORTS_PER_SOL), false, new BN(0), deadline())
+        .accountsStrict({
+          signer: user.publicKey,
+          mintA: tokenMint.publicKey,
+          mintB: NATIVE_MINT,
+          userA: userTokenAccount,
+          userB: userPoolWsolAccount,
+          lpToken,
+          vaultA,
+          vaultB,
+          config,
+          systemProgram: SystemProgram.programId,
+          tokenProgramA: TOKEN_2022_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(hookAccounts())
+        .preInstructions([openWsolAccount()])
+        .signers([user])
+        .rpc();
+    await expectRejected("TransferStatsMissing", buyTokens);
+
+    const exemption = (method: "addVelocityExemption" | "removeVelocityExemption") =>
+      transferHookProgram.methods[method](vaultA)
+        .accountsStrict({ admin: payer.publicKey, hookConfig })
+        .signers([payer])
+        .rpc();
+    await exemption("addVelocityExemption");
+    const before = await getAccount(connection, userTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+    await buyTokens();
+    const after = await getAccount(connection, userTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(after.amount > before.amount).to.equal(true);
+    await exemption("removeVelocityExemption");
+
+    await setLimits(0, 0);
+  });
+
+  it("14. Max Wallet Balance Caps Destinations Except Exempt Vaults", async () => {
+    const admin = { admin: payer.publicKey, hookConfig };
+
+    // Cap at the payer's current balance so one more token goes over
+    const payerBalance = (await getAccount(connection, payerTokenAccount(), "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+    await transferHookProgram.methods
+      .setMaxWalletBalance(new BN(payerBalance.toString()))
+      .accountsStrict(admin)
+      .signers([payer])
+      .rpc();
+    await expectRejected("MaxWalletBalanceExceeded");
+
+    // Pool vaults are exempted the same way; here the payer's account stands in for one
+    await transferHookProgram.methods
+      .addBalanceExemption(payerTokenAccount())
+      .accountsStrict(admin)
+      .signers([payer])
+      .rpc();
+    await transferToPayer();
+
+    await transferHookProgram.methods
+      .removeBalanceExemption(payerTokenAccount())
+      .accountsStrict(admin)
+      .signers([payer])
+      .rpc();
+    await transferHookProgram.methods
+      .setMaxWalletBalance(new BN(0))
+      .accountsStrict(admin)
+      .signers([payer])
+      .rpc();
+  });
+
+  it("15. Vesting Schedule Locks Unvested Tokens", async () => {
+    const [vestingSchedule] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vesting"), tokenMint.publicKey.toBuffer(), user.publicKey.toBuffer()],
+      transferHookProgram.programId
+    );
+
+    // Lock all but one token until a cliff an hour out
+    const balance = (await getAccount(connection, userTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+    const locked = new BN(balance.toString()).subn(oneToken);
+    const now = Math.floor(Date.now() / 1000);
+    await transferHookProgram.methods
+      .createVestingSchedule(user.publicKey, locked, new BN(now), new BN(now + 3600), new BN(now + 7200))
+      .accountsStrict({
+        admin: payer.publicKey,
+        hookConfig,
+        vestingSchedule,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([payer])
+      .rpc();
+
+    await transferToPayer();
+    await expectRejected("TokensLocked");
+
+    const schedule = await transferHookProgram.account.vestingSchedule.fetch(vestingSchedule);
+    expect(schedule.totalAmount.toString()).to.equal(locked.toString());
+  });
+
  
 });
\ No newline at end of file
@@ -0,0 +1,39 @@
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { Amm } from "../target/types/amm";
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+export const hookRegistryAddress = (program: Program<Amm>) =>
+  PublicKey.findProgramAddressSync([Buffer.from("hook-registry")], program.programId)[0];
+
+// The hook registry is global; whichever test file runs first creates it. The provider
+// wallet is the AMM's upgrade authority and becomes the registry admin.
+export const ensureHookRegistry = async (program: Program<Amm>) => {
+  const hookRegistry = hookRegistryAddress(program);
+  if (!(await program.account.hookRegistry.fetchNullable(hookRegistry))) {
+    const admin = program.provider.publicKey;
+    const [programData] = PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE);
+    await program.methods.initializeHookRegistry(admin).accountsStrict({
+      signer: admin,
+      program: program.programId,
+      programData,
+      hookRegistry,
+      systemProgram: SystemProgram.programId,
+    }).rpc();
+  }
+  return hookRegistry;
+};
+
+// Approves any build of `hookProgramId` unless it is already in the registry
+export const ensureHookApproved = async (program: Program<Amm>, hookProgramId: PublicKey) => {
+  const hookRegistry = await ensureHookRegistry(program);
+  const registry = await program.account.hookRegistry.fetch(hookRegistry);
+  if (!registry.hooks.some(hook => hook.programId.equals(hookProgramId))) {
+    await program.methods.approveHook(hookProgramId, null).accountsStrict({
+      admin: program.provider.publicKey,
+      hookRegistry,
+    }).rpc();
+  }
+  return hookRegistry;
+};